@@ -1,4 +1,6 @@
-use sentience_core::{SentienceCore, SentienceTokenAst, SimpleRuntime, Span, ThoughtType, Value};
+use sentience_core::{
+    SentienceCore, SentienceError, SentienceTokenAst, SimpleRuntime, Span, ThoughtType, Value,
+};
 
 fn main() {
     println!("Sentience Core Demo - SRAI Compliant");
@@ -24,7 +26,7 @@ fn main() {
             println!("  Generated tokens: {}", result.tokens.len());
             println!("  Generated edges: {}", result.edges.len());
         }
-        Err(e) => println!("✗ Error: {}", e),
+        Err(errors) => print_errors(&errors),
     }
 
     // Example 2: Parse and execute a Reflection token
@@ -43,7 +45,7 @@ fn main() {
             }
             println!("  Generated tokens: {}", result.tokens.len());
         }
-        Err(e) => println!("✗ Error: {}", e),
+        Err(errors) => print_errors(&errors),
     }
 
     // Example 3: Demonstrate deterministic hashing
@@ -115,7 +117,7 @@ fn main() {
                 );
             }
         }
-        Err(e) => println!("✗ Pipeline error: {}", e),
+        Err(errors) => print_errors(&errors),
     }
 
     println!("\nSentience Core Demo Complete!");
@@ -129,6 +131,12 @@ fn main() {
     println!("  ✓ Complete parse → execute → commit pipeline");
 }
 
+fn print_errors(errors: &[SentienceError]) {
+    for error in errors {
+        println!("✗ Error: {}", error);
+    }
+}
+
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
         return 0.0;
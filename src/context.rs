@@ -1,19 +1,68 @@
+use crate::sentience_core::ast::{Edge, SentienceToken};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::io;
 
+/// Fixed bucket count for [`hash_embedding`]'s bag-of-words vectors. Small enough to keep
+/// collisions cheap to store, large enough that unrelated words rarely collide.
+const EMBED_DIM: usize = 32;
+
+/// Number of `reinforce` calls a short-term key needs before [`AgentContext::promote_reinforced`]
+/// moves it into long-term memory.
+const REINFORCE_THRESHOLD: u32 = 3;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AgentContext {
     pub mem_short: HashMap<String, String>,
     pub mem_long: HashMap<String, String>,
     pub links: HashMap<String, String>,
 
+    /// `SentienceToken`s this agent has committed, keyed by `token.id`.
+    pub tokens: HashMap<String, SentienceToken>,
+    /// Edges between those tokens, keyed by their deterministic `edge_*` hash id.
+    pub edges: HashMap<String, Edge>,
+
+    /// Hashed bag-of-words embeddings produced by `embed`/`train`, keyed by the name they were
+    /// stored under. The optional label is the association `train` gives an embedding so
+    /// `nearest_label` can answer with something other than the key itself.
+    pub embeddings: HashMap<String, (Vec<f32>, Option<String>)>,
+
     #[serde(skip)]
     pub current_agent: Option<crate::types::Statement>,
 
     #[serde(skip)]
     pub output: Option<String>,
+
+    /// Diagnostics [`eval`](crate::eval::eval) has collected across calls: unknown statements,
+    /// failed expression evaluation, and reads of undefined memory keys. Accumulates until a
+    /// caller (a REPL or CLI frontend) drains it, so diagnostics can be rendered as source
+    /// snippets instead of the raw `error: ...` lines also pushed to `output`.
+    #[serde(skip)]
+    pub diagnostics: Vec<crate::diagnostics::Diagnostic>,
+
+    /// Number of times each short-term key has been reinforced (every assignment to it counts,
+    /// inside `evolve` or not), since its count was last reset by
+    /// [`promote_reinforced`](Self::promote_reinforced). Transient: it drives in-session
+    /// promotion decisions rather than anything worth persisting across `save`/`load`.
+    #[serde(skip)]
+    pub reinforce_counts: HashMap<String, u32>,
+
+    /// Directory `import` resolves module names from. Defaults to the process's current
+    /// directory; a file-based frontend can point it at the importing agent's own directory.
+    #[serde(skip)]
+    pub base_dir: std::path::PathBuf,
+
+    /// Agent declarations pulled in by `import`, keyed by the agent's own name, so a block in one
+    /// file can delegate to an agent defined in another.
+    #[serde(skip)]
+    pub agents: HashMap<String, crate::types::Statement>,
+
+    /// Caches and cycle-detects the modules `import` has resolved so far.
+    #[serde(skip)]
+    pub loader: crate::modules::ModuleLoader,
 }
 
 impl AgentContext {
@@ -22,11 +71,27 @@ impl AgentContext {
             mem_short: HashMap::new(),
             mem_long: HashMap::new(),
             links: HashMap::new(),
+            tokens: HashMap::new(),
+            edges: HashMap::new(),
+            embeddings: HashMap::new(),
             current_agent: None,
             output: None,
+            diagnostics: Vec::new(),
+            reinforce_counts: HashMap::new(),
+            base_dir: std::env::current_dir().unwrap_or_default(),
+            agents: HashMap::new(),
+            loader: crate::modules::ModuleLoader::new(),
         }
     }
 
+    pub fn commit_token(&mut self, token: SentienceToken) {
+        self.tokens.insert(token.id.clone(), token);
+    }
+
+    pub fn commit_edge(&mut self, edge: Edge) {
+        self.edges.insert(edge.id.clone(), edge);
+    }
+
     pub fn set_mem(&mut self, target: &str, key: &str, value: &str) {
         match target {
             "short" => {
@@ -47,20 +112,239 @@ impl AgentContext {
         }
     }
 
-    #[allow(dead_code)]
+    /// Like [`get_mem`](Self::get_mem), but distinguishes "key not set" from "key set to the
+    /// empty string" so callers (the expression evaluator) can report an unknown identifier
+    /// instead of silently treating it as `""`.
+    pub fn get_mem_opt(&self, target: &str, key: &str) -> Option<&str> {
+        match target {
+            "short" => self.mem_short.get(key).map(String::as_str),
+            "long" => self.mem_long.get(key).map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// Compute `text`'s hashed bag-of-words embedding and store it under `name`, tagged with
+    /// `label` (the association `train` gives it; `None` for a bare `embed`).
+    pub fn embed_and_store(&mut self, name: &str, text: &str, label: Option<String>) {
+        self.embeddings
+            .insert(name.to_string(), (hash_embedding(text), label));
+    }
+
+    /// Embed `text` and return the label of the stored vector with the highest cosine similarity
+    /// to it, skipping entries with no label. `None` if no labeled embedding has been stored yet.
+    pub fn nearest_label(&self, text: &str) -> Option<String> {
+        let query = hash_embedding(text);
+        self.embeddings
+            .values()
+            .filter_map(|(vector, label)| label.as_ref().map(|label| (cosine(&query, vector), label)))
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, label)| label.clone())
+    }
+
+    /// Count one more reinforcement of `key`, towards [`promote_reinforced`](Self::promote_reinforced)'s threshold.
+    pub fn reinforce(&mut self, key: &str) {
+        *self.reinforce_counts.entry(key.to_string()).or_insert(0) += 1;
+    }
+
+    /// Move every short-term key whose reinforcement count has crossed [`REINFORCE_THRESHOLD`]
+    /// into long-term memory, resetting its count.
+    pub fn promote_reinforced(&mut self) {
+        let promoted: Vec<String> = self
+            .reinforce_counts
+            .iter()
+            .filter(|(_, count)| **count >= REINFORCE_THRESHOLD)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in promoted {
+            if let Some(value) = self.mem_short.get(&key).cloned() {
+                self.mem_long.insert(key.clone(), value);
+            }
+            self.reinforce_counts.remove(&key);
+        }
+    }
+
     pub fn save(&self, path: &str) -> io::Result<()> {
         let serialized = serde_json::to_string_pretty(self)?;
         fs::write(path, serialized)?;
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn load(&mut self, path: &str) -> io::Result<()> {
         let content = fs::read_to_string(path)?;
         let loaded: AgentContext = serde_json::from_str(&content)?;
         self.mem_short = loaded.mem_short;
         self.mem_long = loaded.mem_long;
         self.links = loaded.links;
+        self.tokens = loaded.tokens;
+        self.edges = loaded.edges;
+        self.embeddings = loaded.embeddings;
+        Ok(())
+    }
+
+    /// Union `other`'s memory and token/edge graph into `self`, instead of overwriting it the
+    /// way `load` does. `mem_short`/`mem_long`/`links` entries from `other` win on key collision.
+    /// Tokens with the same id are reconciled by keeping the higher `meta.strength`/`belief` and
+    /// unioning `tags` and `provenance.rules_applied`; edges dedupe on their content-hash id,
+    /// combining `weight` by taking the max.
+    pub fn merge(&mut self, other: &AgentContext) {
+        for (key, value) in &other.mem_short {
+            self.mem_short.insert(key.clone(), value.clone());
+        }
+        for (key, value) in &other.mem_long {
+            self.mem_long.insert(key.clone(), value.clone());
+        }
+        for (key, value) in &other.links {
+            self.links.insert(key.clone(), value.clone());
+        }
+        for (key, value) in &other.embeddings {
+            self.embeddings.insert(key.clone(), value.clone());
+        }
+
+        for (id, incoming) in &other.tokens {
+            match self.tokens.get_mut(id) {
+                Some(existing) => merge_token(existing, incoming),
+                None => {
+                    self.tokens.insert(id.clone(), incoming.clone());
+                }
+            }
+        }
+
+        for (id, incoming) in &other.edges {
+            match self.edges.get_mut(id) {
+                Some(existing) => existing.weight = existing.weight.max(incoming.weight),
+                None => {
+                    self.edges.insert(id.clone(), incoming.clone());
+                }
+            }
+        }
+    }
+
+    /// Load a snapshot from `path` and fold it into this context via [`merge`](Self::merge),
+    /// instead of overwriting existing state the way `load` does.
+    pub fn load_merge(&mut self, path: &str) -> io::Result<()> {
+        let content = fs::read_to_string(path)?;
+        let loaded: AgentContext = serde_json::from_str(&content)?;
+        self.merge(&loaded);
         Ok(())
     }
 }
+
+/// Bag-of-words embedding: lowercase and whitespace-split `text`, hash each token into one of
+/// [`EMBED_DIM`] buckets and increment it, then L2-normalize so cosine similarity is a plain dot
+/// product. Cheap and dependency-free, at the cost of occasional bucket collisions between
+/// unrelated words.
+fn hash_embedding(text: &str) -> Vec<f32> {
+    let mut buckets = vec![0f32; EMBED_DIM];
+    for token in text.to_lowercase().split_whitespace() {
+        let mut hasher = DefaultHasher::new();
+        token.hash(&mut hasher);
+        let bucket = (hasher.finish() as usize) % EMBED_DIM;
+        buckets[bucket] += 1.0;
+    }
+
+    let norm = buckets.iter().map(|b| b * b).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for bucket in buckets.iter_mut() {
+            *bucket /= norm;
+        }
+    }
+    buckets
+}
+
+/// Cosine similarity between two equal-length vectors, assumed already L2-normalized so this is
+/// just their dot product.
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn merge_token(existing: &mut SentienceToken, incoming: &SentienceToken) {
+    existing.meta.strength = existing.meta.strength.max(incoming.meta.strength);
+    existing.meta.belief = existing.meta.belief.max(incoming.meta.belief);
+
+    for tag in &incoming.meta.tags {
+        if !existing.meta.tags.contains(tag) {
+            existing.meta.tags.push(tag.clone());
+        }
+    }
+
+    for rule in &incoming.provenance.rules_applied {
+        if !existing.provenance.rules_applied.contains(rule) {
+            existing.provenance.rules_applied.push(rule.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentience_core::ast::{Provenance, Span, SentienceTokenAst, ThoughtType, TokenMeta};
+
+    fn token(id: &str, strength: f32, belief: f32, tags: &[&str], rules: &[&str]) -> SentienceToken {
+        SentienceToken::new(
+            id.to_string(),
+            SentienceTokenAst::new(ThoughtType::Concept, Span::new(1, 1, 1, 1)),
+            Vec::new(),
+            Provenance {
+                stm_ids: Vec::new(),
+                refnet_id: "test".to_string(),
+                rules_applied: rules.iter().map(|r| r.to_string()).collect(),
+                agent_id: "test".to_string(),
+                step_id: 0,
+                timestamp: 0,
+            },
+            TokenMeta {
+                version: "test".to_string(),
+                strength,
+                belief,
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+            },
+        )
+    }
+
+    #[test]
+    fn merge_reconciles_tokens_with_the_same_id() {
+        let mut ctx = AgentContext::new();
+        ctx.commit_token(token("t1", 0.3, 0.5, &["a"], &["rule_a"]));
+
+        let mut other = AgentContext::new();
+        other.commit_token(token("t1", 0.8, 0.2, &["b"], &["rule_b"]));
+
+        ctx.merge(&other);
+
+        let merged = &ctx.tokens["t1"];
+        assert_eq!(merged.meta.strength, 0.8);
+        assert_eq!(merged.meta.belief, 0.5);
+        assert_eq!(merged.meta.tags, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            merged.provenance.rules_applied,
+            vec!["rule_a".to_string(), "rule_b".to_string()]
+        );
+    }
+
+    #[test]
+    fn merge_takes_the_max_weight_for_duplicate_edges() {
+        let mut ctx = AgentContext::new();
+        ctx.commit_edge(Edge::new(
+            "a".to_string(),
+            "b".to_string(),
+            crate::sentience_core::ast::EdgeType::Supports,
+            0.4,
+            0,
+        ));
+
+        let mut other = AgentContext::new();
+        other.commit_edge(Edge::new(
+            "a".to_string(),
+            "b".to_string(),
+            crate::sentience_core::ast::EdgeType::Supports,
+            0.9,
+            0,
+        ));
+
+        ctx.merge(&other);
+
+        assert_eq!(ctx.edges.len(), 1);
+        assert_eq!(ctx.edges.values().next().unwrap().weight, 0.9);
+    }
+}
@@ -0,0 +1,72 @@
+/// A byte/line/column range into a source buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub line: u32,
+    pub col: u32,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Span {
+    pub fn new(line: u32, col: u32, start: u32, end: u32) -> Self {
+        Span {
+            line,
+            col,
+            start,
+            end,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub message: String,
+    pub severity: Severity,
+}
+
+impl Diagnostic {
+    pub fn error(span: Span, message: impl Into<String>) -> Self {
+        Diagnostic {
+            span,
+            message: message.into(),
+            severity: Severity::Error,
+        }
+    }
+
+    pub fn warning(span: Span, message: impl Into<String>) -> Self {
+        Diagnostic {
+            span,
+            message: message.into(),
+            severity: Severity::Warning,
+        }
+    }
+
+    /// Render this diagnostic as a caret-underlined snippet of `source`.
+    pub fn render(&self, source: &str) -> String {
+        let line_text = source
+            .lines()
+            .nth(self.span.line.saturating_sub(1) as usize)
+            .unwrap_or("");
+        let underline_len = self.span.end.saturating_sub(self.span.start).max(1) as usize;
+        let pointer = format!(
+            "{}{}",
+            " ".repeat(self.span.col.saturating_sub(1) as usize),
+            "^".repeat(underline_len)
+        );
+        let label = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        format!(
+            "{}: {}\n  --> line {}:{}\n{}\n{}",
+            label, self.message, self.span.line, self.span.col, line_text, pointer
+        )
+    }
+}
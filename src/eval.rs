@@ -1,36 +1,296 @@
 use crate::context::AgentContext;
-use crate::types::Statement;
+use crate::diagnostics::{Diagnostic, Span};
+use crate::types::{BinaryOp, Expr, Spanned, Statement, UnaryOp};
+use std::fmt;
 
-fn eval_expr(expr: &str, input: &str, _ctx: &AgentContext) -> String {
-    match expr.trim() {
-        "input" | "msg" => input.to_string(),
-        _ => expr.trim_matches('"').to_string(),
+/// Runtime value an [`Expr`] reduces to. Memory itself stays string-typed (see
+/// [`AgentContext`]), so a value pulled out of `short`/`long` memory is re-typed on read via
+/// [`parse_mem_value`] rather than staying a bare string forever; a quoted literal in the source
+/// (`Expr::Str`) always stays a `Value::Str`.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+}
+
+impl Value {
+    fn as_num(&self) -> f64 {
+        match self {
+            Value::Int(i) => *i as f64,
+            Value::Float(n) => *n,
+            Value::Bool(b) => if *b { 1.0 } else { 0.0 },
+            Value::Str(s) => s.parse().unwrap_or(0.0),
+        }
+    }
+
+    fn as_bool(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            Value::Int(i) => *i != 0,
+            Value::Float(n) => *n != 0.0,
+            Value::Str(s) => !s.is_empty(),
+        }
+    }
+
+    fn to_display_string(&self) -> String {
+        match self {
+            Value::Int(i) => i.to_string(),
+            Value::Float(n) => {
+                if n.fract() == 0.0 {
+                    format!("{}", *n as i64)
+                } else {
+                    n.to_string()
+                }
+            }
+            Value::Str(s) => s.clone(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Str(_) => "string",
+            Value::Bool(_) => "bool",
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, Value::Int(_) | Value::Float(_))
+    }
+}
+
+/// A typed evaluation failure: an unknown identifier, an operator applied to types that don't
+/// support it, or arithmetic that has no defined result. Surfaced to the user as a diagnostic line
+/// rather than collapsing to an empty or garbage value.
+#[derive(Clone, Debug, PartialEq)]
+enum EvalError {
+    UnknownIdentifier(String),
+    BinaryTypeMismatch {
+        op: &'static str,
+        lhs: &'static str,
+        rhs: &'static str,
+    },
+    UnaryTypeMismatch {
+        op: &'static str,
+        operand: &'static str,
+    },
+    DivisionByZero,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownIdentifier(name) => write!(f, "unknown identifier '{}'", name),
+            EvalError::BinaryTypeMismatch { op, lhs, rhs } => {
+                write!(f, "cannot apply '{}' to {} and {}", op, lhs, rhs)
+            }
+            EvalError::UnaryTypeMismatch { op, operand } => {
+                write!(f, "cannot apply '{}' to {}", op, operand)
+            }
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Re-type a raw memory string the way the evaluator would have produced it, had it been an
+/// expression result itself: the leading numeric/boolean grammar wins, otherwise it's a string.
+fn parse_mem_value(raw: &str) -> Value {
+    if let Ok(i) = raw.parse::<i64>() {
+        Value::Int(i)
+    } else if let Ok(n) = raw.parse::<f64>() {
+        Value::Float(n)
+    } else if raw == "true" {
+        Value::Bool(true)
+    } else if raw == "false" {
+        Value::Bool(false)
+    } else {
+        Value::Str(raw.to_string())
+    }
+}
+
+fn numeric_binary(
+    op: &'static str,
+    lhs: Value,
+    rhs: Value,
+    int_op: fn(i64, i64) -> i64,
+    float_op: fn(f64, f64) -> f64,
+) -> Result<Value, EvalError> {
+    match (&lhs, &rhs) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(int_op(*a, *b))),
+        _ if lhs.is_numeric() && rhs.is_numeric() => {
+            Ok(Value::Float(float_op(lhs.as_num(), rhs.as_num())))
+        }
+        _ => Err(EvalError::BinaryTypeMismatch {
+            op,
+            lhs: lhs.type_name(),
+            rhs: rhs.type_name(),
+        }),
+    }
+}
+
+fn eval_binary(op: BinaryOp, lhs: Value, rhs: Value) -> Result<Value, EvalError> {
+    match op {
+        BinaryOp::Add => match (&lhs, &rhs) {
+            (Value::Str(a), Value::Str(b)) => Ok(Value::Str(format!("{}{}", a, b))),
+            _ if lhs.is_numeric() && rhs.is_numeric() => numeric_binary("+", lhs, rhs, |a, b| a + b, |a, b| a + b),
+            _ => Err(EvalError::BinaryTypeMismatch {
+                op: "+",
+                lhs: lhs.type_name(),
+                rhs: rhs.type_name(),
+            }),
+        },
+        BinaryOp::Sub => numeric_binary("-", lhs, rhs, |a, b| a - b, |a, b| a - b),
+        BinaryOp::Mul => numeric_binary("*", lhs, rhs, |a, b| a * b, |a, b| a * b),
+        BinaryOp::Div => {
+            if !lhs.is_numeric() || !rhs.is_numeric() {
+                return Err(EvalError::BinaryTypeMismatch {
+                    op: "/",
+                    lhs: lhs.type_name(),
+                    rhs: rhs.type_name(),
+                });
+            }
+            if rhs.as_num() == 0.0 {
+                return Err(EvalError::DivisionByZero);
+            }
+            Ok(Value::Float(lhs.as_num() / rhs.as_num()))
+        }
+        // Comparisons coerce both sides instead of rejecting mixed types: `"3" > 2` and
+        // `short.count == 0` are both meant to work without the author spelling out a cast.
+        BinaryOp::Gt => Ok(Value::Bool(lhs.as_num() > rhs.as_num())),
+        BinaryOp::Gte => Ok(Value::Bool(lhs.as_num() >= rhs.as_num())),
+        BinaryOp::Lt => Ok(Value::Bool(lhs.as_num() < rhs.as_num())),
+        BinaryOp::Lte => Ok(Value::Bool(lhs.as_num() <= rhs.as_num())),
+        BinaryOp::Eq => Ok(Value::Bool(lhs.to_display_string() == rhs.to_display_string())),
+        BinaryOp::NotEq => Ok(Value::Bool(lhs.to_display_string() != rhs.to_display_string())),
+        BinaryOp::And => Ok(Value::Bool(lhs.as_bool() && rhs.as_bool())),
+        BinaryOp::Or => Ok(Value::Bool(lhs.as_bool() || rhs.as_bool())),
+    }
+}
+
+/// Resolve a bare identifier: `input`/`msg` refer to the value passed into the current `on
+/// input`/`train`/`evolve` block; anything else is looked up in short-term memory first, then
+/// long-term memory. An identifier present in neither is an error rather than an empty string.
+fn resolve_ident(name: &str, input: &str, ctx: &AgentContext) -> Result<Value, EvalError> {
+    match name {
+        "input" | "msg" => Ok(Value::Str(input.to_string())),
+        _ => ctx
+            .get_mem_opt("short", name)
+            .or_else(|| ctx.get_mem_opt("long", name))
+            .map(parse_mem_value)
+            .ok_or_else(|| EvalError::UnknownIdentifier(name.to_string())),
+    }
+}
+
+/// Resolve an explicit `target.key` / `target[key]` memory access. Unlike [`resolve_ident`], the
+/// bucket is named explicitly, so there's no short-then-long fallback.
+fn resolve_mem(target: &str, key: &str, ctx: &AgentContext) -> Result<Value, EvalError> {
+    ctx.get_mem_opt(target, key)
+        .map(parse_mem_value)
+        .ok_or_else(|| EvalError::UnknownIdentifier(format!("{}.{}", target, key)))
+}
+
+fn eval_expr_value(expr: &Expr, input: &str, ctx: &AgentContext) -> Result<Value, EvalError> {
+    match expr {
+        Expr::Num(n) => {
+            if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+                Ok(Value::Int(*n as i64))
+            } else {
+                Ok(Value::Float(*n))
+            }
+        }
+        Expr::Str(s) => Ok(Value::Str(s.clone())),
+        Expr::Bool(b) => Ok(Value::Bool(*b)),
+        Expr::Ident(name) => resolve_ident(name, input, ctx),
+        Expr::Unary(UnaryOp::Neg, inner) => match eval_expr_value(inner, input, ctx)? {
+            Value::Int(i) => Ok(Value::Int(-i)),
+            Value::Float(n) => Ok(Value::Float(-n)),
+            other => Err(EvalError::UnaryTypeMismatch {
+                op: "-",
+                operand: other.type_name(),
+            }),
+        },
+        Expr::Unary(UnaryOp::Not, inner) => {
+            Ok(Value::Bool(!eval_expr_value(inner, input, ctx)?.as_bool()))
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs = eval_expr_value(lhs, input, ctx)?;
+            let rhs = eval_expr_value(rhs, input, ctx)?;
+            eval_binary(*op, lhs, rhs)
+        }
+        Expr::Member(base, field) => resolve_mem(&mem_target_name(base), field, ctx),
+        Expr::Index(base, index) => {
+            let target = mem_target_name(base);
+            let key = eval_expr_value(index, input, ctx)?.to_display_string();
+            resolve_mem(&target, &key, ctx)
+        }
+    }
+}
+
+/// `short.msg` and `short["msg"]` both name a memory bucket via their base identifier. Any other
+/// base (e.g. the `mem.short` in `mem.short["msg"]`) names no bucket, so the lookup fails as an
+/// unknown identifier.
+fn mem_target_name(base: &Expr) -> String {
+    match base {
+        Expr::Ident(name) => name.clone(),
+        _ => String::new(),
+    }
+}
+
+fn eval_expr(expr: &Expr, input: &str, ctx: &AgentContext) -> Result<String, EvalError> {
+    Ok(eval_expr_value(expr, input, ctx)?.to_display_string())
+}
+
+/// Evaluate `expr`, pushing a diagnostic anchored at `span` into both `output` and
+/// `ctx.diagnostics`, and returning `None` instead of a placeholder value, on failure.
+fn eval_or_diagnose(
+    expr: &Expr,
+    span: Span,
+    input: &str,
+    ctx: &mut AgentContext,
+    output: &mut Vec<String>,
+) -> Option<Value> {
+    match eval_expr_value(expr, input, ctx) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            output.push(format!("error: {}", e));
+            ctx.diagnostics.push(Diagnostic::error(span, e.to_string()));
+            None
+        }
     }
 }
 
 /// Evaluate a single AST statement in the given context.
 pub fn eval(
-    stmt: &Statement,
+    stmt: &Spanned<Statement>,
     indent: &str,
     input: &str,
     ctx: &mut AgentContext,
     output: &mut Vec<String>,
 ) {
-    match stmt {
+    let span = stmt.span;
+    match &stmt.node {
         Statement::AgentDeclaration { name, body } => {
             output.push(format!("Agent: {}", name));
             for inner in body.iter() {
-                match inner {
+                match &inner.node {
                     Statement::MemDeclaration { target } => {
                         output.push(format!("  Init mem: {}", target));
                     }
-                    Statement::Goal(text) => {
-                        output.push(format!("  Goal: \"{}\"", text));
+                    Statement::Goal(expr) => {
+                        if let Some(val) = eval_or_diagnose(expr, inner.span, input, ctx, output) {
+                            output.push(format!("  Goal: {}", val.to_display_string()));
+                        }
                     }
                     _ => {}
                 }
             }
-            ctx.current_agent = Some(stmt.clone());
+            ctx.current_agent = Some(stmt.node.clone());
             output.push(format!("Agent: {} [registered]", name));
         }
         Statement::MemDeclaration { .. } => {}
@@ -46,46 +306,152 @@ pub fn eval(
                 eval(inner, &nested_indent, input, ctx, output);
             }
         }
-        Statement::ReflectAccess { mem_target, key } => {
-            let val = match mem_target.as_str() {
-                "short" => ctx.get_mem("short", key),
-                "long" => ctx.get_mem("long", key),
-                _ => String::new(),
-            };
-            ctx.output = Some(val.clone());
-            output.push(format!("{}{}", indent, val));
+        Statement::ReflectAccess { mem_target, key } => match resolve_mem(mem_target, key, ctx) {
+            Ok(val) => {
+                let val = val.to_display_string();
+                ctx.output = Some(val.clone());
+                output.push(format!("{}{}", indent, val));
+            }
+            Err(e) => {
+                output.push(format!("{}error: {}", indent, e));
+                ctx.diagnostics.push(Diagnostic::error(span, e.to_string()));
+            }
+        },
+        Statement::Train { body } => {
+            for inner in body.iter() {
+                eval(inner, indent, input, ctx, output);
+            }
+        }
+        Statement::TrainAssociation { input: expr, label } => {
+            if let Some(value) = eval_or_diagnose(expr, span, input, ctx, output) {
+                ctx.embed_and_store(label, &value.to_display_string(), Some(label.clone()));
+            }
+        }
+        Statement::Evolve { body } => {
+            for inner in body.iter() {
+                eval(inner, indent, input, ctx, output);
+            }
+            ctx.promote_reinforced();
         }
-        Statement::Train { .. } => {}
-        Statement::Evolve { .. } => {}
         Statement::Goal(_) => {}
-        Statement::Embed { .. } => {}
-        Statement::IfContextIncludes { values, body } => {
+        Statement::Embed { source, target } => match resolve_ident(source, input, ctx) {
+            Ok(value) => ctx.embed_and_store(target, &value.to_display_string(), None),
+            Err(e) => {
+                output.push(format!("{}error: {}", indent, e));
+                ctx.diagnostics.push(Diagnostic::error(span, e.to_string()));
+            }
+        },
+        Statement::Import { name } => {
+            let start_dir = ctx.base_dir.clone();
+            match ctx.loader.load(name, &start_dir) {
+                Ok(statements) => {
+                    for inner in statements.iter() {
+                        if let Statement::AgentDeclaration {
+                            name: agent_name, ..
+                        } = &inner.node
+                        {
+                            ctx.agents.insert(agent_name.clone(), inner.node.clone());
+                        }
+                    }
+                }
+                Err(e) => {
+                    output.push(format!("{}error: {}", indent, e));
+                    ctx.diagnostics.push(Diagnostic::error(span, e.to_string()));
+                }
+            }
+        }
+        Statement::Enter { name } => match ctx.agents.get(name).cloned() {
+            Some(Statement::AgentDeclaration { body, .. }) => {
+                for inner in body.iter() {
+                    if let Statement::Goal(expr) = &inner.node {
+                        if let Some(val) = eval_or_diagnose(expr, inner.span, input, ctx, output) {
+                            output.push(format!("{}Goal: {}", indent, val.to_display_string()));
+                        }
+                    }
+                }
+            }
+            _ => {
+                let e = format!("agent '{}' not found", name);
+                output.push(format!("{}error: {}", indent, e));
+                ctx.diagnostics.push(Diagnostic::error(span, e));
+            }
+        },
+        Statement::Recall { source, target } => match resolve_ident(source, input, ctx) {
+            Ok(value) => {
+                if let Some(label) = ctx.nearest_label(&value.to_display_string()) {
+                    ctx.set_mem("short", target, &label);
+                }
+            }
+            Err(e) => {
+                output.push(format!("{}error: {}", indent, e));
+                ctx.diagnostics.push(Diagnostic::error(span, e.to_string()));
+            }
+        },
+        Statement::IfContextIncludes {
+            values,
+            condition,
+            body,
+        } => {
             let current_val = ctx.get_mem("short", "msg");
-            for v in values.iter() {
-                if current_val.contains(v) {
-                    for inner in body.iter() {
-                        eval(inner, indent, input, ctx, output);
+            let includes_any = values.iter().any(|v| {
+                eval_or_diagnose(v, span, input, ctx, output)
+                    .map(|val| current_val.contains(&val.to_display_string()))
+                    .unwrap_or(false)
+            });
+            let condition_holds = condition
+                .as_ref()
+                .map(|c| {
+                    eval_or_diagnose(c, span, input, ctx, output)
+                        .map(|val| val.as_bool())
+                        .unwrap_or(false)
+                })
+                .unwrap_or(true);
+            if includes_any && condition_holds {
+                for inner in body.iter() {
+                    eval(inner, indent, input, ctx, output);
+                }
+            }
+        }
+        Statement::Match { target, arms } => {
+            if let Some(value) = eval_or_diagnose(target, span, input, ctx, output) {
+                let subject = value.to_display_string();
+                for arm in arms.iter() {
+                    if let Some(bindings) = arm.pattern.match_input(&subject) {
+                        for (name, captured) in bindings {
+                            ctx.set_mem("short", &name, &captured);
+                        }
+                        for inner in arm.body.iter() {
+                            eval(inner, indent, input, ctx, output);
+                        }
+                        break;
                     }
-                    break;
                 }
             }
         }
         Statement::Print(text) => {
             output.push(format!("{}{}", indent, text));
         }
-        Statement::Assignment(name, expr) => {
-            if name == "output" {
-                let val = eval_expr(expr, input, ctx);
-                ctx.output = Some(val.clone());
-                output.push(val);
-                return;
+        Statement::Assignment(name, expr) => match eval_expr(expr, input, ctx) {
+            Ok(val) => {
+                if name == "output" {
+                    ctx.output = Some(val.clone());
+                    output.push(val);
+                } else {
+                    ctx.set_mem("short", name, &val);
+                    ctx.reinforce(name);
+                }
             }
-
-            let val = eval_expr(expr, input, ctx);
-            ctx.set_mem("short", name, &val);
-        }
+            Err(e) => {
+                output.push(format!("{}error: {}", indent, e));
+                ctx.diagnostics.push(Diagnostic::error(span, e.to_string()));
+            }
+        },
         Statement::Unknown(text) => {
             output.push(format!("{}Unknown statement: {}", indent, text));
+            ctx.diagnostics.push(Diagnostic::error(
+                span,
+                format!("unknown statement: {}", text),
+            ));
         }
     }
 }
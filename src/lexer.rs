@@ -1,9 +1,12 @@
+use crate::diagnostics::{Diagnostic, Span};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenType {
     Illegal,
     Eof,
     Ident,
     String,
+    Number,
     Arrow,
     LParen,
     RParen,
@@ -11,6 +14,7 @@ pub enum TokenType {
     RBrace,
     Dot,
     Colon,
+    Comma,
     LBracket,
     RBracket,
     Agent,
@@ -20,20 +24,40 @@ pub enum TokenType {
     Reflect,
     Train,
     If,
+    Match,
+    When,
     Enter,
     Embed,
+    Recall,
+    Import,
     Link,
     Input,
     Print,
     Evolve,
     LinkArrow,
     Equal,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    EqEq,
+    NotEq,
+    And,
+    Or,
+    Not,
+    True,
+    False,
 }
 
 #[derive(Clone, Debug)]
 pub struct Token {
     pub token_type: TokenType,
     pub literal: String,
+    pub span: Span,
 }
 
 impl Token {
@@ -41,8 +65,14 @@ impl Token {
         Token {
             token_type,
             literal: literal.to_string(),
+            span: Span::new(1, 1, 0, 0),
         }
     }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
 }
 
 pub struct Lexer<'a> {
@@ -50,6 +80,9 @@ pub struct Lexer<'a> {
     position: usize,
     read_position: usize,
     ch: Option<char>,
+    line: u32,
+    col: u32,
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl<'a> Lexer<'a> {
@@ -59,12 +92,28 @@ impl<'a> Lexer<'a> {
             position: 0,
             read_position: 0,
             ch: None,
+            line: 1,
+            col: 1,
+            diagnostics: Vec::new(),
         };
         l.read_char();
         l
     }
 
+    /// Drain and return any diagnostics (unterminated strings, illegal chars) collected so far.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
     fn read_char(&mut self) {
+        if let Some(c) = self.ch {
+            if c == '\n' {
+                self.line += 1;
+                self.col = 1;
+            } else {
+                self.col += 1;
+            }
+        }
         if self.read_position >= self.input.len() {
             self.ch = None;
         } else {
@@ -76,6 +125,15 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    fn span_from(&self, start_pos: usize, start_line: u32, start_col: u32) -> Span {
+        Span::new(
+            start_line,
+            start_col,
+            start_pos as u32,
+            self.position as u32,
+        )
+    }
+
     fn peek_char(&self) -> Option<char> {
         if self.read_position >= self.input.len() {
             None
@@ -86,9 +144,14 @@ impl<'a> Lexer<'a> {
 
     pub fn next_token(&mut self) -> Token {
         self.skip_whitespace();
+        let start_pos = self.position;
+        let start_line = self.line;
+        let start_col = self.col;
         let tok = match self.ch {
-            // Some('=') => Token::new(TokenType::Assign, "="),
-            Some('=') => Token::new(TokenType::Equal, "="),
+            Some('+') => Token::new(TokenType::Plus, "+"),
+            Some('*') => Token::new(TokenType::Star, "*"),
+            Some('/') => Token::new(TokenType::Slash, "/"),
+            Some(',') => Token::new(TokenType::Comma, ","),
             Some('(') => Token::new(TokenType::LParen, "("),
             Some(')') => Token::new(TokenType::RParen, ")"),
             Some('{') => Token::new(TokenType::LBrace, "{"),
@@ -97,16 +160,46 @@ impl<'a> Lexer<'a> {
             Some(':') => Token::new(TokenType::Colon, ":"),
             Some('[') => Token::new(TokenType::LBracket, "["),
             Some(']') => Token::new(TokenType::RBracket, "]"),
+            Some('=') => {
+                if let Some('=') = self.peek_char() {
+                    self.read_char();
+                    Token::new(TokenType::EqEq, "==")
+                } else {
+                    Token::new(TokenType::Equal, "=")
+                }
+            }
+            Some('!') => {
+                if let Some('=') = self.peek_char() {
+                    self.read_char();
+                    Token::new(TokenType::NotEq, "!=")
+                } else {
+                    let span = self.span_from(start_pos, start_line, start_col);
+                    self.diagnostics
+                        .push(Diagnostic::error(span, "unexpected character '!'"));
+                    Token::new(TokenType::Illegal, "!")
+                }
+            }
+            Some('>') => {
+                if let Some('=') = self.peek_char() {
+                    self.read_char();
+                    Token::new(TokenType::Gte, ">=")
+                } else {
+                    Token::new(TokenType::Gt, ">")
+                }
+            }
             Some('-') => {
                 if let Some('>') = self.peek_char() {
                     self.read_char();
                     Token::new(TokenType::Arrow, "->")
                 } else {
-                    Token::new(TokenType::Illegal, &self.ch.unwrap().to_string())
+                    Token::new(TokenType::Minus, "-")
                 }
             }
             Some('<') => {
-                if let Some('-') = self.peek_char() {
+                if let Some('=') = self.peek_char() {
+                    self.read_char();
+                    Token::new(TokenType::Lte, "<=")
+                } else if let Some('-') = self.peek_char() {
                     let second = self.peek_char();
                     if let Some('-') = second {
                         let ahead = self.input[self.read_position + 1..].chars().next();
@@ -116,35 +209,54 @@ impl<'a> Lexer<'a> {
                             self.read_char();
                             Token::new(TokenType::LinkArrow, "<->")
                         } else {
+                            let span = self.span_from(start_pos, start_line, start_col);
+                            self.diagnostics.push(Diagnostic::error(
+                                span,
+                                "malformed link arrow, expected '<->'",
+                            ));
                             Token::new(TokenType::Illegal, &self.ch.unwrap().to_string())
                         }
                     } else {
+                        let span = self.span_from(start_pos, start_line, start_col);
+                        self.diagnostics.push(Diagnostic::error(
+                            span,
+                            "malformed link arrow, expected '<->'",
+                        ));
                         Token::new(TokenType::Illegal, &self.ch.unwrap().to_string())
                     }
                 } else {
-                    Token::new(TokenType::Illegal, &self.ch.unwrap().to_string())
+                    Token::new(TokenType::Lt, "<")
                 }
             }
             Some('"') => {
-                let literal = self.read_string();
-                Token::new(TokenType::String, &literal)
+                let literal = self.read_string(start_pos, start_line, start_col);
+                let span = self.span_from(start_pos, start_line, start_col);
+                return Token::new(TokenType::String, &literal).with_span(span);
             }
             None => Token::new(TokenType::Eof, ""),
             Some(c) => {
                 if is_letter(c) {
                     let literal = self.read_identifier();
                     let token_type = lookup_ident(&literal);
-                    return Token::new(token_type, &literal);
+                    let span = self.span_from(start_pos, start_line, start_col);
+                    return Token::new(token_type, &literal).with_span(span);
                 } else if c.is_ascii_digit() {
                     let literal = self.read_number();
-                    return Token::new(TokenType::String, &literal);
+                    let span = self.span_from(start_pos, start_line, start_col);
+                    return Token::new(TokenType::Number, &literal).with_span(span);
                 } else {
+                    let span = self.span_from(start_pos, start_line, start_col);
+                    self.diagnostics.push(Diagnostic::error(
+                        span,
+                        format!("unexpected character '{}'", c),
+                    ));
                     Token::new(TokenType::Illegal, &c.to_string())
                 }
             }
         };
         self.read_char();
-        tok
+        let span = self.span_from(start_pos, start_line, start_col);
+        tok.with_span(span)
     }
 
     fn skip_whitespace(&mut self) {
@@ -178,10 +290,20 @@ impl<'a> Lexer<'a> {
                 break;
             }
         }
+        if self.ch == Some('.') && self.peek_char().map_or(false, |c| c.is_ascii_digit()) {
+            self.read_char();
+            while let Some(c) = self.ch {
+                if c.is_ascii_digit() {
+                    self.read_char();
+                } else {
+                    break;
+                }
+            }
+        }
         self.input[position..self.position].to_string()
     }
 
-    fn read_string(&mut self) -> String {
+    fn read_string(&mut self, start_pos: usize, start_line: u32, start_col: u32) -> String {
         self.read_char();
         let start = self.position;
         while let Some(c) = self.ch {
@@ -191,7 +313,13 @@ impl<'a> Lexer<'a> {
             self.read_char();
         }
         let literal = self.input[start..self.position].to_string();
-        self.read_char();
+        if self.ch.is_none() {
+            let span = self.span_from(start_pos, start_line, start_col);
+            self.diagnostics
+                .push(Diagnostic::error(span, "unterminated string literal"));
+        } else {
+            self.read_char();
+        }
         literal
     }
 }
@@ -209,12 +337,21 @@ fn lookup_ident(ident: &str) -> TokenType {
         "reflect" => TokenType::Reflect,
         "train" => TokenType::Train,
         "if" => TokenType::If,
+        "match" => TokenType::Match,
+        "when" => TokenType::When,
         "enter" => TokenType::Enter,
         "embed" => TokenType::Embed,
+        "recall" => TokenType::Recall,
+        "import" => TokenType::Import,
         "link" => TokenType::Link,
         "input" => TokenType::Input,
         "print" => TokenType::Print,
         "evolve" => TokenType::Evolve,
+        "and" => TokenType::And,
+        "or" => TokenType::Or,
+        "not" => TokenType::Not,
+        "true" => TokenType::True,
+        "false" => TokenType::False,
         _ => TokenType::Ident,
     }
 }
@@ -1,7 +1,10 @@
 pub mod context;
+pub mod diagnostics;
 pub mod eval;
 pub mod lexer;
+pub mod modules;
 pub mod parser;
+pub mod sentience_core;
 pub mod types;
 
 use context::AgentContext;
@@ -26,8 +29,11 @@ impl SentienceAgent {
         let full_input = code.trim();
         let mut lexer = Lexer::new(full_input);
         let mut parser = Parser::new(&mut lexer);
-        let program = parser.parse_program();
+        let (program, errors) = parser.parse_program();
         let mut output = Vec::new();
+        for error in &errors {
+            output.push(error.render(full_input));
+        }
         for stmt in program.statements {
             eval(&stmt, "", "", &mut self.ctx, &mut output);
         }
@@ -42,7 +48,7 @@ impl SentienceAgent {
 
         if let Some(Statement::AgentDeclaration { body, .. }) = current_agent {
             for stmt in body {
-                if let Statement::OnInput { body, .. } = stmt {
+                if let Statement::OnInput { body, .. } = stmt.node {
                     for inner in body {
                         eval(&inner, "", input, &mut self.ctx, &mut output);
                     }
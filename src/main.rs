@@ -1,18 +1,40 @@
 mod context;
+mod diagnostics;
 mod eval;
 mod lexer;
+mod modules;
 mod parser;
+mod sentience_core;
 mod types;
 
 use context::AgentContext;
 use eval::eval;
-use lexer::Lexer;
-use parser::Parser;
+use lexer::{Lexer, TokenType};
+use parser::{ParseError, Parser};
+use sentience_core::ast::{Program, SentienceTokenAst, Span, ThoughtType, Value};
+use sentience_core::runtime::SimpleRuntime;
+use sentience_core::SentienceCore;
 use std::io::{self, BufRead, Write};
 use types::Statement;
 
-fn print_prompt() {
-    print!(">>> ");
+/// Which DSL the REPL is currently reading: the original agent DSL (`.agent`, the default), or
+/// the Sentience Core DSL (`.core`) whose `Runtime` persists across prompts so committed tokens
+/// and STM carry over between entries.
+enum Mode {
+    Agent,
+    Core,
+}
+
+fn print_prompt(mode: &Mode) {
+    match mode {
+        Mode::Agent => print!(">>> "),
+        Mode::Core => print!("core>>> "),
+    }
+    io::stdout().flush().unwrap();
+}
+
+fn print_continuation_prompt() {
+    print!("... ");
     io::stdout().flush().unwrap();
 }
 
@@ -22,55 +44,197 @@ fn main() {
     let stdin = io::stdin();
     let mut lines = stdin.lock().lines();
     let mut ctx = AgentContext::new();
+    let mut core = SentienceCore::new(Box::new(SimpleRuntime::new()));
+    let mut mode = Mode::Agent;
 
     let mut buffer: Vec<String> = Vec::new();
-    let mut depth = 0;
 
-    print_prompt();
+    print_prompt(&mode);
 
     while let Some(Ok(line)) = lines.next() {
         let trimmed = line.trim();
 
-        if trimmed.is_empty() && depth == 0 {
-            print_prompt();
+        if trimmed.is_empty() && buffer.is_empty() {
+            print_prompt(&mode);
             continue;
         }
 
-        if depth == 0 && trimmed.starts_with('.') {
-            handle_command(trimmed, &mut ctx);
-            print_prompt();
+        if buffer.is_empty() && trimmed.starts_with('.') {
+            match trimmed {
+                ".core" => {
+                    mode = Mode::Core;
+                    println!("Switched to Sentience Core mode.");
+                }
+                ".agent" => {
+                    mode = Mode::Agent;
+                    println!("Switched to agent mode.");
+                }
+                _ => match mode {
+                    Mode::Core => handle_core_command(trimmed, &mut core),
+                    Mode::Agent => handle_command(trimmed, &mut ctx, &mut core),
+                },
+            }
+            print_prompt(&mode);
             continue;
         }
 
-        depth += trimmed.matches('{').count();
-        depth -= trimmed.matches('}').count();
         buffer.push(trimmed.to_string());
+        let full_input = buffer.join(" ");
+
+        match mode {
+            Mode::Agent => {
+                if is_incomplete(&full_input) {
+                    print_continuation_prompt();
+                    continue;
+                }
 
-        if depth == 0 {
-            let full_input = buffer.join(" ");
-            let mut lexer = Lexer::new(&full_input);
-            let mut parser = Parser::new(&mut lexer);
-            let program = parser.parse_program();
-            for stmt in program.statements {
-                let mut output = Vec::new();
-                eval(&stmt, "", "", &mut ctx, &mut output);
-                if !output.is_empty() {
+                let mut lexer = Lexer::new(&full_input);
+                let mut parser = Parser::new(&mut lexer);
+                let (program, errors) = parser.parse_program();
+                for error in &errors {
+                    println!("{}", error.render(&full_input));
+                }
+                for stmt in program.statements {
+                    let mut output = Vec::new();
+                    eval(&stmt, "", "", &mut ctx, &mut output);
                     for line in output {
                         println!("{}", line);
                     }
+                    for diagnostic in ctx.diagnostics.drain(..) {
+                        println!("{}", diagnostic.render(&full_input));
+                    }
+                }
+            }
+            Mode::Core => {
+                if is_incomplete_core(&full_input) {
+                    print_continuation_prompt();
+                    continue;
+                }
+
+                match core.process_step(&full_input) {
+                    Ok(result) => print_execution_result(&result),
+                    Err(errors) => {
+                        for error in &errors {
+                            println!("{}", error.render(&full_input));
+                        }
+                    }
                 }
             }
-            buffer.clear();
-            print_prompt();
         }
+
+        buffer.clear();
+        print_prompt(&mode);
+    }
+}
+
+/// True when `source` is a dangling Sentience Core block (e.g. `reflect {` with no closing `}`
+/// yet): the parser runs out of real tokens before finishing the last statement and reports an
+/// unexpected `Eof`. The REPL keeps accumulating lines in that case instead of running a
+/// half-typed block.
+fn is_incomplete_core(source: &str) -> bool {
+    match sentience_core::parser::parse_program(source) {
+        Ok(_) => false,
+        Err(e) => e.code == "E_UNEXPECTED_TOKEN" && e.message.ends_with("found Eof"),
     }
 }
 
-fn handle_command(line: &str, ctx: &mut AgentContext) {
+/// Handle a dot-command in Sentience Core mode: `.save`/`.load` snapshot the Cortex itself
+/// (see `sentience_core::store`), unlike the agent DSL's `.save`/`.load` which persist an
+/// `AgentContext`.
+fn handle_core_command(line: &str, core: &mut SentienceCore) {
+    let after_dot = &line[1..];
+    let (cmd, rest) = after_dot.split_once(' ').unwrap_or((after_dot, ""));
+    let path = rest.trim();
+
+    match cmd {
+        "save" => match core.save(path) {
+            Ok(()) => println!("Saved Cortex to {}", path),
+            Err(e) => println!("Failed to save Cortex to {}: {}", path, e),
+        },
+        "load" => match core.load(path) {
+            Ok(()) => println!("Loaded Cortex from {}", path),
+            Err(e) => println!("Failed to load Cortex from {}: {}", path, e),
+        },
+        _ => println!("Unknown command: .{}", cmd),
+    }
+}
+
+/// Print one step's `ExecutionResult`: the id of every token committed this step, any block
+/// reasons the Superego raised, and the edges produced.
+fn print_execution_result(result: &sentience_core::runtime::ExecutionResult) {
+    for token in &result.tokens {
+        println!("token {} ({})", token.id, token.ast.ttype);
+    }
+    for reason in &result.blocked {
+        println!("blocked: {}", reason);
+    }
+    for edge in &result.edges {
+        println!(
+            "edge {} --{}--> {}",
+            edge.source_id, edge.edge_type, edge.target_id
+        );
+    }
+}
+
+/// True when `source` shouldn't be parsed yet: it has unclosed `{`/`(`/`[`, or the parser runs
+/// out of input before finishing the last statement (a dangling `agent Name` / `on input(x)`
+/// header with no body yet). The REPL keeps accumulating lines in either case instead of running
+/// a half-typed program.
+fn is_incomplete(source: &str) -> bool {
+    if has_unbalanced_delimiters(source) {
+        return true;
+    }
+
+    let mut lexer = Lexer::new(source);
+    let mut parser = Parser::new(&mut lexer);
+    let (_, errors) = parser.parse_program();
+    errors.last().map_or(false, ends_at_eof)
+}
+
+fn has_unbalanced_delimiters(source: &str) -> bool {
+    let mut lexer = Lexer::new(source);
+    let mut depth = 0i32;
+    loop {
+        let tok = lexer.next_token();
+        match tok.token_type {
+            TokenType::LBrace | TokenType::LParen | TokenType::LBracket => depth += 1,
+            TokenType::RBrace | TokenType::RParen | TokenType::RBracket => depth -= 1,
+            TokenType::Eof => break,
+            _ => {}
+        }
+    }
+    depth > 0
+}
+
+/// A `ParseError` produced by running out of tokens (rather than finding the wrong one) means
+/// the statement is merely unfinished, not malformed.
+fn ends_at_eof(error: &ParseError) -> bool {
+    error.message.ends_with("found ''")
+}
+
+fn handle_command(line: &str, ctx: &mut AgentContext, core: &mut SentienceCore) {
     let after_dot = &line[1..];
     let (cmd, rest) = after_dot.split_once(' ').unwrap_or((after_dot, ""));
     let input_value = rest.trim();
 
+    match cmd {
+        "save" => {
+            match ctx.save(input_value) {
+                Ok(()) => println!("Saved context to {}", input_value),
+                Err(e) => println!("Failed to save context to {}: {}", input_value, e),
+            }
+            return;
+        }
+        "load" => {
+            match ctx.load(input_value) {
+                Ok(()) => println!("Loaded context from {}", input_value),
+                Err(e) => println!("Failed to load context from {}: {}", input_value, e),
+            }
+            return;
+        }
+        _ => {}
+    }
+
     if ctx.current_agent.is_none() {
         println!("No agent registered.");
         return;
@@ -78,7 +242,7 @@ fn handle_command(line: &str, ctx: &mut AgentContext) {
 
     if let Some(Statement::AgentDeclaration { body, .. }) = ctx.current_agent.clone() {
         for stmt in body {
-            match (cmd, &stmt) {
+            match (cmd, &stmt.node) {
                 ("input", Statement::OnInput { param, body }) => {
                     ctx.set_mem("short", param, input_value);
                     let mut output = Vec::new();
@@ -88,6 +252,15 @@ fn handle_command(line: &str, ctx: &mut AgentContext) {
                     for line in output {
                         println!("{}", line);
                     }
+                    run_through_cognitive_cycle(
+                        core,
+                        ThoughtType::Percept,
+                        vec![
+                            ("modality", Value::Str("text".to_string())),
+                            ("content", Value::Str(input_value.to_string())),
+                        ],
+                        input_value,
+                    );
                     return;
                 }
 
@@ -100,6 +273,12 @@ fn handle_command(line: &str, ctx: &mut AgentContext) {
                     for line in output {
                         println!("{}", line);
                     }
+                    run_through_cognitive_cycle(
+                        core,
+                        ThoughtType::Concept,
+                        vec![("summary", Value::Str(input_value.to_string()))],
+                        input_value,
+                    );
                     return;
                 }
 
@@ -112,6 +291,12 @@ fn handle_command(line: &str, ctx: &mut AgentContext) {
                     for line in output {
                         println!("{}", line);
                     }
+                    run_through_cognitive_cycle(
+                        core,
+                        ThoughtType::Reflection,
+                        vec![("ops", Value::List(vec![Value::Str("evolve".to_string())]))],
+                        input_value,
+                    );
                     return;
                 }
 
@@ -125,3 +310,28 @@ fn handle_command(line: &str, ctx: &mut AgentContext) {
         }
     }
 }
+
+/// Run the agent DSL's already-evaluated `.input`/`.train`/`.evolve` text through the Sentience
+/// Core cognitive cycle as a `ttype` token, so it's gated by the Superego and committed to the
+/// Cortex the same way a `.core`-mode statement would be, and print the outcome like `.core` mode
+/// does. `fields` supplies whatever that `ttype`'s executor reads (e.g. `content` for a Percept,
+/// `ops` for a Reflection); `source` is only used to render a cognitive-cycle error, if any.
+fn run_through_cognitive_cycle(
+    core: &mut SentienceCore,
+    ttype: ThoughtType,
+    fields: Vec<(&str, Value)>,
+    source: &str,
+) {
+    let mut ast = SentienceTokenAst::new(ttype, Span::new(1, 1, 1, 1));
+    for (key, value) in fields {
+        ast = ast.with_field(key.to_string(), value);
+    }
+
+    let program = Program {
+        statements: vec![ast],
+    };
+    match core.execute(&program) {
+        Ok(result) => print_execution_result(&result),
+        Err(error) => println!("{}", error.render(source)),
+    }
+}
@@ -0,0 +1,190 @@
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::types::{Spanned, Statement};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Why an `import <name>` statement couldn't be resolved or loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModuleError {
+    NotFound(String),
+    Cycle(String),
+    Io(String),
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleError::NotFound(name) => write!(f, "module '{}' not found", name),
+            ModuleError::Cycle(name) => write!(f, "import cycle detected at '{}'", name),
+            ModuleError::Io(e) => write!(f, "could not read module: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ModuleError {}
+
+/// Loads and caches `.sent` agent modules referenced by `import` statements. Resolution starts
+/// at `start_dir` and checks each of its ancestors in turn, up to the filesystem root, trying
+/// `<name>.sent` directly and a conventional `agents/<name>.sent` subdirectory at each level.
+#[derive(Debug, Default)]
+pub struct ModuleLoader {
+    /// Already-parsed modules, keyed by their canonicalized path, so re-importing the same file
+    /// from two places doesn't re-evaluate it.
+    modules: HashMap<PathBuf, Vec<Spanned<Statement>>>,
+    /// Canonical paths of imports currently in progress, used to detect cycles.
+    loading: Vec<PathBuf>,
+}
+
+impl ModuleLoader {
+    pub fn new() -> Self {
+        ModuleLoader {
+            modules: HashMap::new(),
+            loading: Vec::new(),
+        }
+    }
+
+    /// Find `<name>.sent` by checking `start_dir`, then each of its ancestors in turn, also
+    /// trying an `agents/<name>.sent` subdirectory at each level. `None` if the walk reaches the
+    /// filesystem root without a match.
+    fn resolve(name: &str, start_dir: &Path) -> Option<PathBuf> {
+        let filename = format!("{}.sent", name);
+        let mut dir = Some(start_dir);
+        while let Some(d) = dir {
+            let direct = d.join(&filename);
+            if direct.is_file() {
+                return Some(direct);
+            }
+            let nested = d.join("agents").join(&filename);
+            if nested.is_file() {
+                return Some(nested);
+            }
+            dir = d.parent();
+        }
+        None
+    }
+
+    /// Resolve and parse `name`'s module, searching upward from `start_dir`, recursively loading
+    /// whatever it in turn `import`s. Returns the cached statements if this path was already
+    /// loaded; fails with [`ModuleError::Cycle`] instead of recursing if the import is already in
+    /// progress further up the call stack.
+    pub fn load(
+        &mut self,
+        name: &str,
+        start_dir: &Path,
+    ) -> Result<Vec<Spanned<Statement>>, ModuleError> {
+        let path = Self::resolve(name, start_dir).ok_or_else(|| ModuleError::NotFound(name.to_string()))?;
+        let canonical = fs::canonicalize(&path).map_err(|e| ModuleError::Io(e.to_string()))?;
+
+        if let Some(cached) = self.modules.get(&canonical) {
+            return Ok(cached.clone());
+        }
+        if self.loading.contains(&canonical) {
+            return Err(ModuleError::Cycle(name.to_string()));
+        }
+
+        self.loading.push(canonical.clone());
+        let result = self.parse_and_load_imports(&canonical);
+        self.loading.pop();
+        let statements = result?;
+
+        self.modules.insert(canonical, statements.clone());
+        Ok(statements)
+    }
+
+    /// Parse `canonical`'s source and recursively load every module it `import`s, so a cycle
+    /// anywhere in the chain is caught by the `loading` stack rather than overflowing it.
+    fn parse_and_load_imports(&mut self, canonical: &Path) -> Result<Vec<Spanned<Statement>>, ModuleError> {
+        let source = fs::read_to_string(canonical).map_err(|e| ModuleError::Io(e.to_string()))?;
+        let mut lexer = Lexer::new(&source);
+        let mut parser = Parser::new(&mut lexer);
+        let (program, _errors) = parser.parse_program();
+
+        let dir = canonical.parent().unwrap_or_else(|| Path::new("."));
+        for stmt in &program.statements {
+            if let Statement::Import { name } = &stmt.node {
+                self.load(name, dir)?;
+            }
+        }
+
+        Ok(program.statements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    /// A fresh scratch directory under the system temp dir, unique to this test run.
+    fn scratch_dir() -> PathBuf {
+        let id = COUNTER.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("sentience_modules_test_{}_{}", std::process::id(), id));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn loads_a_module_from_the_current_directory() {
+        let dir = scratch_dir();
+        fs::write(dir.join("greeter.sent"), "agent Greeter {\n  mem short\n}").unwrap();
+
+        let mut loader = ModuleLoader::new();
+        let statements = loader.load("greeter", &dir).unwrap();
+
+        assert_eq!(statements.len(), 1);
+        assert!(matches!(
+            &statements[0].node,
+            Statement::AgentDeclaration { name, .. } if name == "Greeter"
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolves_a_module_from_a_sibling_agents_directory_one_level_up() {
+        let dir = scratch_dir();
+        let child = dir.join("project");
+        let agents = dir.join("agents");
+        fs::create_dir_all(&child).unwrap();
+        fs::create_dir_all(&agents).unwrap();
+        fs::write(agents.join("helper.sent"), "agent Helper {\n  mem short\n}").unwrap();
+
+        let mut loader = ModuleLoader::new();
+        let statements = loader.load("helper", &child).unwrap();
+
+        assert_eq!(statements.len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_an_import_cycle_instead_of_recursing_forever() {
+        let dir = scratch_dir();
+        fs::write(dir.join("a.sent"), "import b").unwrap();
+        fs::write(dir.join("b.sent"), "import a").unwrap();
+
+        let mut loader = ModuleLoader::new();
+        let err = loader.load("a", &dir).unwrap_err();
+
+        assert_eq!(err, ModuleError::Cycle("a".to_string()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_an_unresolvable_import() {
+        let dir = scratch_dir();
+        let mut loader = ModuleLoader::new();
+
+        let err = loader.load("nonexistent", &dir).unwrap_err();
+
+        assert_eq!(err, ModuleError::NotFound("nonexistent".to_string()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
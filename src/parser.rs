@@ -1,38 +1,176 @@
+use crate::diagnostics::{Diagnostic, Span};
 use crate::lexer::{Lexer, Token, TokenType};
-use crate::types::{Program, Statement};
+use crate::types::{BinaryOp, Expr, MatchArm, Pattern, Program, Spanned, Statement, UnaryOp};
+
+/// Binding power of prefix `-`/`not`. Higher than every binary operator below so `-2 * 3`
+/// parses as `(-2) * 3`, not `-(2 * 3)`.
+const PREFIX_BP: u8 = 13;
+
+/// `(left_bp, right_bp)` for each binary operator, in ascending precedence. Every operator here
+/// is left-associative (`right_bp = left_bp + 1`); a right-associative operator would instead
+/// get `right_bp < left_bp` so it recurses back into operands of its own precedence.
+fn infix_binding_power(tt: &TokenType) -> Option<(u8, u8)> {
+    match tt {
+        TokenType::Or => Some((1, 2)),
+        TokenType::And => Some((3, 4)),
+        TokenType::EqEq | TokenType::NotEq => Some((5, 6)),
+        TokenType::Gt | TokenType::Gte | TokenType::Lt | TokenType::Lte => Some((7, 8)),
+        TokenType::Plus | TokenType::Minus => Some((9, 10)),
+        TokenType::Star | TokenType::Slash => Some((11, 12)),
+        _ => None,
+    }
+}
+
+fn to_binary_op(tt: &TokenType) -> BinaryOp {
+    match tt {
+        TokenType::Plus => BinaryOp::Add,
+        TokenType::Minus => BinaryOp::Sub,
+        TokenType::Star => BinaryOp::Mul,
+        TokenType::Slash => BinaryOp::Div,
+        TokenType::Gt => BinaryOp::Gt,
+        TokenType::Gte => BinaryOp::Gte,
+        TokenType::Lt => BinaryOp::Lt,
+        TokenType::Lte => BinaryOp::Lte,
+        TokenType::EqEq => BinaryOp::Eq,
+        TokenType::NotEq => BinaryOp::NotEq,
+        TokenType::And => BinaryOp::And,
+        TokenType::Or => BinaryOp::Or,
+        other => unreachable!("to_binary_op called with non-operator token {:?}", other),
+    }
+}
+
+/// A parse-time error: a span into the source, a human-readable message, and (when the failure
+/// was a straightforward token mismatch) what was expected instead of what was found.
+#[derive(Clone, Debug)]
+pub struct ParseError {
+    pub span: Span,
+    pub message: String,
+    pub expected: Option<String>,
+}
+
+impl ParseError {
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        ParseError {
+            span,
+            message: message.into(),
+            expected: None,
+        }
+    }
+
+    /// An "expected X, found Y" mismatch at `span`.
+    pub fn expected(span: Span, expected: &str, found: &str) -> Self {
+        ParseError {
+            span,
+            message: format!("expected {}, found '{}'", expected, found),
+            expected: Some(expected.to_string()),
+        }
+    }
+
+    /// Render this error as a caret-underlined snippet of `source`, the same shape as
+    /// [`Diagnostic::render`].
+    pub fn render(&self, source: &str) -> String {
+        let line_text = source
+            .lines()
+            .nth(self.span.line.saturating_sub(1) as usize)
+            .unwrap_or("");
+        let underline_len = self.span.end.saturating_sub(self.span.start).max(1) as usize;
+        let pointer = format!(
+            "{}{}",
+            " ".repeat(self.span.col.saturating_sub(1) as usize),
+            "^".repeat(underline_len)
+        );
+        format!(
+            "error: {}\n  --> line {}:{}\n{}\n{}",
+            self.message, self.span.line, self.span.col, line_text, pointer
+        )
+    }
+}
+
+impl From<Diagnostic> for ParseError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        ParseError {
+            span: diagnostic.span,
+            message: diagnostic.message,
+            expected: None,
+        }
+    }
+}
 
 pub struct Parser<'a> {
     lexer: &'a mut Lexer<'a>,
     cur_token: Token,
     peek_token: Token,
+    errors: Vec<ParseError>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(lexer: &'a mut Lexer<'a>) -> Self {
         let first = lexer.next_token();
         let second = lexer.next_token();
+        let mut errors = Vec::new();
+        errors.extend(lexer.take_diagnostics().into_iter().map(ParseError::from));
         Parser {
             lexer,
             cur_token: first,
             peek_token: second,
+            errors,
         }
     }
 
     fn next_token(&mut self) {
         self.cur_token = std::mem::replace(&mut self.peek_token, self.lexer.next_token());
+        self.errors
+            .extend(self.lexer.take_diagnostics().into_iter().map(ParseError::from));
     }
 
-    pub fn parse_program(&mut self) -> Program {
+    /// Parse the whole input, recovering from malformed statements instead of dropping them
+    /// silently: every statement that fails to parse contributes a [`ParseError`] and the parser
+    /// resynchronizes at the next statement-starting keyword (or `}`) rather than aborting.
+    pub fn parse_program(&mut self) -> (Program, Vec<ParseError>) {
         let mut program = Program {
             statements: Vec::new(),
         };
         while self.cur_token.token_type != TokenType::Eof {
-            if let Some(stmt) = self.parse_statement() {
-                program.statements.push(stmt);
+            let start = self.cur_token.span;
+            match self.parse_statement() {
+                Some(stmt) => {
+                    let end = self.cur_token.span;
+                    let span = Span::new(start.line, start.col, start.start, end.end);
+                    program.statements.push(Spanned { node: stmt, span });
+                    self.next_token();
+                }
+                None => {
+                    self.synchronize();
+                }
+            }
+        }
+        (program, std::mem::take(&mut self.errors))
+    }
+
+    /// Skip tokens until the next statement-starting keyword or `}`, so one malformed
+    /// construct doesn't swallow everything that follows it.
+    fn synchronize(&mut self) {
+        self.next_token();
+        while self.cur_token.token_type != TokenType::Eof {
+            match self.cur_token.token_type {
+                TokenType::Agent
+                | TokenType::Mem
+                | TokenType::On
+                | TokenType::Reflect
+                | TokenType::Train
+                | TokenType::Evolve
+                | TokenType::Goal
+                | TokenType::Embed
+                | TokenType::Recall
+                | TokenType::Import
+                | TokenType::Enter
+                | TokenType::If
+                | TokenType::Match
+                | TokenType::Print
+                | TokenType::RBrace => return,
+                _ => self.next_token(),
             }
-            self.next_token();
         }
-        program
     }
 
     fn parse_statement(&mut self) -> Option<Statement> {
@@ -45,41 +183,182 @@ impl<'a> Parser<'a> {
             TokenType::Evolve => self.parse_evolve(),
             TokenType::Goal => self.parse_goal(),
             TokenType::Embed => self.parse_embed(),
+            TokenType::Recall => self.parse_recall(),
+            TokenType::Import => self.parse_import(),
+            TokenType::Enter => self.parse_enter(),
             TokenType::If => self.parse_if_context_includes(),
+            TokenType::Match => self.parse_match(),
             TokenType::Print => self.parse_print(),
             _ => {
                 if self.cur_token.token_type == TokenType::Ident
                     && self.peek_token.token_type == TokenType::Equal
                 {
                     let key = self.cur_token.literal.clone();
-                    self.next_token();
-                    self.next_token();
-                    let value = self.cur_token.literal.clone();
+                    self.next_token(); // cur_token = '='
+                    self.next_token(); // cur_token = first token of rhs
+                    let value = self.parse_expr(0)?;
                     return Some(Statement::Assignment(key, value));
                 }
 
+                self.errors.push(ParseError::new(
+                    self.cur_token.span,
+                    format!("unexpected token '{}'", self.cur_token.literal),
+                ));
                 Some(Statement::Unknown(self.cur_token.literal.clone()))
             }
         }
     }
 
-    fn parse_agent(&mut self) -> Option<Statement> {
-        self.next_token();
-        let name = self.cur_token.literal.clone();
-        if self.peek_token.token_type != TokenType::LBrace {
-            return None;
-        }
-        self.next_token();
+    /// Parse the body of a block construct (`{ ... }`), recovering statement-by-statement so a
+    /// malformed inner statement doesn't abort the whole block. Each statement is paired with
+    /// its source span, the same way [`parse_program`](Self::parse_program) spans its top-level
+    /// statements, so `eval` can anchor diagnostics at the inner statement that caused them.
+    fn parse_block(&mut self) -> Vec<Spanned<Statement>> {
         let mut body = Vec::new();
         self.next_token();
         while self.cur_token.token_type != TokenType::RBrace
             && self.cur_token.token_type != TokenType::Eof
         {
-            if let Some(inner) = self.parse_statement() {
-                body.push(inner);
+            let start = self.cur_token.span;
+            match self.parse_statement() {
+                Some(inner) => {
+                    let end = self.cur_token.span;
+                    let span = Span::new(start.line, start.col, start.start, end.end);
+                    body.push(Spanned { node: inner, span });
+                    self.next_token();
+                }
+                None => self.synchronize(),
             }
-            self.next_token();
         }
+        body
+    }
+
+    /// Precedence-climbing (Pratt) expression parser: `cur_token` must be positioned on the
+    /// first token of the expression on entry. Parses a prefix/atom, then repeatedly consumes
+    /// an infix operator whose left binding power exceeds `min_bp`, recursing into the right
+    /// operand with that operator's right binding power.
+    fn parse_expr(&mut self, min_bp: u8) -> Option<Expr> {
+        let mut lhs = self.parse_prefix_expr()?;
+
+        loop {
+            let (l_bp, r_bp) = match infix_binding_power(&self.peek_token.token_type) {
+                Some(bp) => bp,
+                None => break,
+            };
+            if l_bp < min_bp {
+                break;
+            }
+            self.next_token(); // cur_token = operator
+            let op = to_binary_op(&self.cur_token.token_type);
+            self.next_token(); // cur_token = first token of rhs
+            let rhs = self.parse_expr(r_bp)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+
+        Some(lhs)
+    }
+
+    fn parse_prefix_expr(&mut self) -> Option<Expr> {
+        let mut expr = match self.cur_token.token_type {
+            TokenType::Number => {
+                let n: f64 = self.cur_token.literal.parse().unwrap_or(0.0);
+                Expr::Num(n)
+            }
+            TokenType::String => Expr::Str(self.cur_token.literal.clone()),
+            TokenType::True => Expr::Bool(true),
+            TokenType::False => Expr::Bool(false),
+            TokenType::Ident => Expr::Ident(self.cur_token.literal.clone()),
+            TokenType::Minus => {
+                self.next_token();
+                let operand = self.parse_expr(PREFIX_BP)?;
+                Expr::Unary(UnaryOp::Neg, Box::new(operand))
+            }
+            TokenType::Not => {
+                self.next_token();
+                let operand = self.parse_expr(PREFIX_BP)?;
+                Expr::Unary(UnaryOp::Not, Box::new(operand))
+            }
+            TokenType::LParen => {
+                self.next_token();
+                let inner = self.parse_expr(0)?;
+                if !self.expect_peek(TokenType::RParen, "')'") {
+                    return None;
+                }
+                self.next_token();
+                inner
+            }
+            _ => {
+                self.errors.push(ParseError::expected(
+                    self.cur_token.span,
+                    "an expression",
+                    &self.cur_token.literal,
+                ));
+                return None;
+            }
+        };
+
+        // Member/index access bind tighter than any binary operator.
+        loop {
+            expr = match self.peek_token.token_type {
+                TokenType::Dot => {
+                    self.next_token(); // cur_token = '.'
+                    self.next_token(); // cur_token = field name
+                    if !self.expect_cur(TokenType::Ident, "a field name") {
+                        return None;
+                    }
+                    Expr::Member(Box::new(expr), self.cur_token.literal.clone())
+                }
+                TokenType::LBracket => {
+                    self.next_token(); // cur_token = '['
+                    self.next_token(); // cur_token = first token of the index expr
+                    let index = self.parse_expr(0)?;
+                    if !self.expect_peek(TokenType::RBracket, "']'") {
+                        return None;
+                    }
+                    self.next_token(); // cur_token = ']'
+                    Expr::Index(Box::new(expr), Box::new(index))
+                }
+                _ => break,
+            };
+        }
+
+        Some(expr)
+    }
+
+    fn expect_peek(&mut self, tt: TokenType, what: &str) -> bool {
+        if self.peek_token.token_type != tt {
+            self.errors.push(ParseError::expected(
+                self.peek_token.span,
+                what,
+                &self.peek_token.literal,
+            ));
+            false
+        } else {
+            true
+        }
+    }
+
+    fn expect_cur(&mut self, tt: TokenType, what: &str) -> bool {
+        if self.cur_token.token_type != tt {
+            self.errors.push(ParseError::expected(
+                self.cur_token.span,
+                what,
+                &self.cur_token.literal,
+            ));
+            false
+        } else {
+            true
+        }
+    }
+
+    fn parse_agent(&mut self) -> Option<Statement> {
+        self.next_token();
+        let name = self.cur_token.literal.clone();
+        if !self.expect_peek(TokenType::LBrace, "'{'") {
+            return None;
+        }
+        self.next_token();
+        let body = self.parse_block();
         Some(Statement::AgentDeclaration { name, body })
     }
 
@@ -89,35 +368,40 @@ impl<'a> Parser<'a> {
         Some(Statement::MemDeclaration { target })
     }
 
+    /// `import <name>`: `<name>` names a `.sent` file, resolved at eval time by walking up from
+    /// `AgentContext::base_dir`.
+    fn parse_import(&mut self) -> Option<Statement> {
+        self.next_token();
+        let name = self.cur_token.literal.clone();
+        Some(Statement::Import { name })
+    }
+
+    fn parse_enter(&mut self) -> Option<Statement> {
+        self.next_token();
+        let name = self.cur_token.literal.clone();
+        Some(Statement::Enter { name })
+    }
+
     fn parse_on_input(&mut self) -> Option<Statement> {
         self.next_token();
-        if self.cur_token.token_type != TokenType::Input {
+        if !self.expect_cur(TokenType::Input, "'input'") {
             return None;
         }
         self.next_token();
-        if self.cur_token.token_type != TokenType::LParen {
+        if !self.expect_cur(TokenType::LParen, "'('") {
             return None;
         }
         self.next_token();
         let param = self.cur_token.literal.clone();
         self.next_token();
-        if self.cur_token.token_type != TokenType::RParen {
+        if !self.expect_cur(TokenType::RParen, "')'") {
             return None;
         }
         self.next_token();
-        if self.cur_token.token_type != TokenType::LBrace {
+        if !self.expect_cur(TokenType::LBrace, "'{'") {
             return None;
         }
-        let mut body = Vec::new();
-        self.next_token();
-        while self.cur_token.token_type != TokenType::RBrace
-            && self.cur_token.token_type != TokenType::Eof
-        {
-            if let Some(s) = self.parse_statement() {
-                body.push(s);
-            }
-            self.next_token();
-        }
+        let body = self.parse_block();
         Some(Statement::OnInput { param, body })
     }
 
@@ -127,50 +411,54 @@ impl<'a> Parser<'a> {
             self.next_token(); // cur_token == LBrace
             self.next_token(); // cur_token == Mem
 
-            if self.cur_token.token_type != TokenType::Mem {
+            if !self.expect_cur(TokenType::Mem, "'mem'") {
                 return None;
             }
 
+            let start = self.cur_token.span;
             let (mem_target, key) = self.expect_dot_and_bracket()?;
+            let end = self.cur_token.span;
+            let access_span = Span::new(start.line, start.col, start.start, end.end);
             while self.cur_token.token_type != TokenType::RBrace
                 && self.cur_token.token_type != TokenType::Eof
             {
                 self.next_token();
             }
             return Some(Statement::Reflect {
-                body: vec![Statement::ReflectAccess { mem_target, key }],
+                body: vec![Spanned {
+                    node: Statement::ReflectAccess { mem_target, key },
+                    span: access_span,
+                }],
             });
         }
 
         self.next_token();
-        if self.cur_token.token_type != TokenType::Mem {
+        if !self.expect_cur(TokenType::Mem, "'mem'") {
             return None;
         }
-        if let Some((mem_target, key)) = self.expect_dot_and_bracket() {
-            return Some(Statement::ReflectAccess { mem_target, key });
-        }
-        None
+        let (mem_target, key) = self.expect_dot_and_bracket()?;
+        Some(Statement::ReflectAccess { mem_target, key })
     }
 
     fn expect_dot_and_bracket(&mut self) -> Option<(String, String)> {
         self.next_token();
-        if self.cur_token.token_type != TokenType::Dot {
+        if !self.expect_cur(TokenType::Dot, "'.'") {
             return None;
         }
 
         self.next_token();
-        if self.cur_token.token_type != TokenType::Ident {
+        if !self.expect_cur(TokenType::Ident, "an identifier") {
             return None;
         }
         let mem_target = self.cur_token.literal.clone();
 
         self.next_token();
-        if self.cur_token.token_type != TokenType::LBracket {
+        if !self.expect_cur(TokenType::LBracket, "'['") {
             return None;
         }
 
         self.next_token();
-        if self.cur_token.token_type != TokenType::String {
+        if !self.expect_cur(TokenType::String, "a string") {
             return None;
         }
         let key = self.cur_token.literal.clone();
@@ -179,58 +467,56 @@ impl<'a> Parser<'a> {
         if self.cur_token.token_type != TokenType::RBracket
             && self.cur_token.token_type != TokenType::RBrace
         {
+            self.errors.push(ParseError::expected(
+                self.cur_token.span,
+                "']' or '}'",
+                &self.cur_token.literal,
+            ));
             return None;
         }
 
         Some((mem_target, key))
     }
 
+    /// Parse either a full `train { ... }` block (triggered by the REPL's `.train` command) or a
+    /// single-line `train <expr> -> "label"` that embeds `expr` and associates it with `label`.
     fn parse_train(&mut self) -> Option<Statement> {
+        if self.peek_token.token_type == TokenType::LBrace {
+            self.next_token();
+            let body = self.parse_block();
+            return Some(Statement::Train { body });
+        }
+
         self.next_token();
-        if self.cur_token.token_type != TokenType::LBrace {
+        let input = self.parse_expr(0)?;
+        self.next_token();
+        if !self.expect_cur(TokenType::Arrow, "'->'") {
             return None;
         }
-        let mut body = Vec::new();
         self.next_token();
-        while self.cur_token.token_type != TokenType::RBrace
-            && self.cur_token.token_type != TokenType::Eof
-        {
-            if let Some(s) = self.parse_statement() {
-                body.push(s);
-            }
-            self.next_token();
+        if !self.expect_cur(TokenType::String, "a string label") {
+            return None;
         }
-        Some(Statement::Train { body })
+        let label = self.cur_token.literal.clone();
+        Some(Statement::TrainAssociation { input, label })
     }
 
     fn parse_evolve(&mut self) -> Option<Statement> {
         self.next_token();
-        if self.cur_token.token_type != TokenType::LBrace {
+        if !self.expect_cur(TokenType::LBrace, "'{'") {
             return None;
         }
-        let mut body = Vec::new();
-        self.next_token();
-        while self.cur_token.token_type != TokenType::RBrace
-            && self.cur_token.token_type != TokenType::Eof
-        {
-            if let Some(s) = self.parse_statement() {
-                body.push(s);
-            }
-            self.next_token();
-        }
+        let body = self.parse_block();
         Some(Statement::Evolve { body })
     }
 
     fn parse_goal(&mut self) -> Option<Statement> {
         self.next_token();
-        if self.cur_token.token_type != TokenType::Colon {
+        if !self.expect_cur(TokenType::Colon, "':'") {
             return None;
         }
         self.next_token();
-        if self.cur_token.token_type != TokenType::String {
-            return None;
-        }
-        let value = self.cur_token.literal.clone();
+        let value = self.parse_expr(0)?;
         Some(Statement::Goal(value))
     }
 
@@ -238,7 +524,7 @@ impl<'a> Parser<'a> {
         self.next_token();
         let source = self.cur_token.literal.clone();
         self.next_token();
-        if self.cur_token.token_type != TokenType::Arrow {
+        if !self.expect_cur(TokenType::Arrow, "'->'") {
             return None;
         }
         self.next_token();
@@ -252,50 +538,118 @@ impl<'a> Parser<'a> {
         Some(Statement::Embed { source, target })
     }
 
+    /// `recall <ident> -> <dotted.path>`: same arrow-target grammar as [`parse_embed`](Self::parse_embed).
+    fn parse_recall(&mut self) -> Option<Statement> {
+        self.next_token();
+        let source = self.cur_token.literal.clone();
+        self.next_token();
+        if !self.expect_cur(TokenType::Arrow, "'->'") {
+            return None;
+        }
+        self.next_token();
+        let mut parts = vec![self.cur_token.literal.clone()];
+        self.next_token();
+        if self.cur_token.token_type == TokenType::Dot {
+            self.next_token();
+            parts.push(self.cur_token.literal.clone());
+        }
+        let target = parts.join(".");
+        Some(Statement::Recall { source, target })
+    }
+
     fn parse_if_context_includes(&mut self) -> Option<Statement> {
         self.next_token();
         if self.cur_token.token_type != TokenType::Ident || self.cur_token.literal != "context" {
+            self.errors.push(ParseError::expected(
+                self.cur_token.span,
+                "'context'",
+                &self.cur_token.literal,
+            ));
             return None;
         }
         self.next_token();
         if self.cur_token.token_type != TokenType::Ident || self.cur_token.literal != "includes" {
+            self.errors.push(ParseError::expected(
+                self.cur_token.span,
+                "'includes'",
+                &self.cur_token.literal,
+            ));
             return None;
         }
         self.next_token();
-        if self.cur_token.token_type != TokenType::LBracket {
+        if !self.expect_cur(TokenType::LBracket, "'['") {
             return None;
         }
         let mut values = Vec::new();
-        loop {
-            self.next_token();
-            if self.cur_token.token_type == TokenType::String {
-                values.push(self.cur_token.literal.clone());
-            } else if self.cur_token.token_type == TokenType::RBracket {
-                break;
-            } else {
-                return None;
+        self.next_token(); // cur_token = first element, or ']'
+        while self.cur_token.token_type != TokenType::RBracket {
+            let value = self.parse_expr(0)?;
+            values.push(value);
+            self.next_token(); // cur_token = ',' or ']'
+            if self.cur_token.token_type == TokenType::Comma {
+                self.next_token();
             }
         }
-        self.next_token();
-        if self.cur_token.token_type != TokenType::LBrace {
+
+        self.next_token(); // cur_token = token after ']'
+        let condition = if self.cur_token.token_type == TokenType::And {
+            self.next_token(); // cur_token = first token of the condition expr
+            let cond = self.parse_expr(0)?;
+            self.next_token(); // cur_token = '{'
+            Some(cond)
+        } else {
+            None
+        };
+
+        if !self.expect_cur(TokenType::LBrace, "'{'") {
             return None;
         }
-        let mut body = Vec::new();
-        self.next_token();
+        let body = self.parse_block();
+        Some(Statement::IfContextIncludes {
+            values,
+            condition,
+            body,
+        })
+    }
+
+    /// Parse `match <expr> { when "<pattern>" { ... } ... }`. Each `when` arm's pattern is a
+    /// string literal tokenized into literal words and `$name` metavariables by [`Pattern::parse`].
+    fn parse_match(&mut self) -> Option<Statement> {
+        self.next_token(); // cur_token = first token of the target expr
+        let target = self.parse_expr(0)?;
+        if !self.expect_peek(TokenType::LBrace, "'{'") {
+            return None;
+        }
+        self.next_token(); // cur_token = '{'
+        self.next_token(); // cur_token = 'when' or '}'
+
+        let mut arms = Vec::new();
         while self.cur_token.token_type != TokenType::RBrace
             && self.cur_token.token_type != TokenType::Eof
         {
-            if let Some(s) = self.parse_statement() {
-                body.push(s);
+            if !self.expect_cur(TokenType::When, "'when'") {
+                return None;
             }
             self.next_token();
+            if !self.expect_cur(TokenType::String, "a pattern string") {
+                return None;
+            }
+            let pattern = Pattern::parse(&self.cur_token.literal);
+            self.next_token();
+            if !self.expect_cur(TokenType::LBrace, "'{'") {
+                return None;
+            }
+            let body = self.parse_block();
+            arms.push(MatchArm { pattern, body });
+            self.next_token(); // cur_token = next 'when' or '}'
         }
-        Some(Statement::IfContextIncludes { values, body })
+
+        Some(Statement::Match { target, arms })
     }
 
     fn parse_print(&mut self) -> Option<Statement> {
         self.next_token();
-        if self.cur_token.token_type != TokenType::String {
+        if !self.expect_cur(TokenType::String, "a string") {
             return None;
         }
         let val = self.cur_token.literal.clone();
@@ -307,7 +661,7 @@ impl<'a> Parser<'a> {
 mod tests {
     use super::*;
     use crate::lexer::Lexer;
-    use crate::types::Statement;
+    use crate::types::{PatternSegment, Statement};
 
     #[test]
     fn parse_simple_agent() {
@@ -326,30 +680,32 @@ mod tests {
         "#;
         let mut lexer = Lexer::new(input);
         let mut parser = Parser::new(&mut lexer);
-        let program = parser.parse_program();
+        let (program, errors) = parser.parse_program();
 
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
         assert_eq!(program.statements.len(), 1);
-        match &program.statements[0] {
+        match &program.statements[0].node {
             Statement::AgentDeclaration { name, body } => {
                 assert_eq!(name, "Echo");
                 assert!(
                     body.iter().any(|s| {
                         matches!(
-                            s,
+                            &s.node,
                             Statement::MemDeclaration { target } if target == "short"
                         )
                     }),
                     "expected MemDeclaration {{ target: \"short\" }}"
                 );
                 assert!(
-                    body.iter()
-                        .any(|s| { matches!(s, Statement::Goal(g) if g == "Store and reflect") }),
-                    "expected Goal(\"Store and reflect\")"
+                    body.iter().any(|s| {
+                        matches!(&s.node, Statement::Goal(Expr::Str(g)) if g == "Store and reflect")
+                    }),
+                    "expected Goal(Expr::Str(\"Store and reflect\"))"
                 );
                 assert!(
                     body.iter().any(|s| {
                         matches!(
-                            s,
+                            &s.node,
                             Statement::OnInput { param, body: _ } if param == "msg"
                         )
                     }),
@@ -357,11 +713,204 @@ mod tests {
                 );
                 assert!(
                     body.iter()
-                        .any(|s| matches!(s, Statement::Train { body: _ })),
+                        .any(|s| matches!(&s.node, Statement::Train { body: _ })),
                     "expected Train {{ body }}"
                 );
             }
             _ => panic!("Expected AgentDeclaration"),
         }
     }
+
+    #[test]
+    fn recovers_from_a_malformed_agent_and_keeps_parsing() {
+        // `agent Broken` is missing its `{`, so parsing it fails; the parser should
+        // resynchronize at the next `agent` keyword rather than losing `agent Ok`.
+        let input = r#"
+            agent Broken
+            agent Ok {
+              mem short
+            }
+        "#;
+        let mut lexer = Lexer::new(input);
+        let mut parser = Parser::new(&mut lexer);
+        let (program, errors) = parser.parse_program();
+
+        assert!(!errors.is_empty(), "expected a parse error for the malformed agent");
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0].node {
+            Statement::AgentDeclaration { name, .. } => assert_eq!(name, "Ok"),
+            other => panic!("expected the recovered AgentDeclaration, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_arithmetic_with_precedence() {
+        let input = "count = 2 + 1 * 3";
+        let mut lexer = Lexer::new(input);
+        let mut parser = Parser::new(&mut lexer);
+        let (program, errors) = parser.parse_program();
+
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
+        match &program.statements[0].node {
+            Statement::Assignment(name, expr) => {
+                assert_eq!(name, "count");
+                // `*` binds tighter than `+`: `2 + (1 * 3)`.
+                assert_eq!(
+                    expr,
+                    &Expr::Binary(
+                        BinaryOp::Add,
+                        Box::new(Expr::Num(2.0)),
+                        Box::new(Expr::Binary(
+                            BinaryOp::Mul,
+                            Box::new(Expr::Num(1.0)),
+                            Box::new(Expr::Num(3.0)),
+                        )),
+                    )
+                );
+            }
+            other => panic!("expected Assignment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_goal_and_if_condition_expressions() {
+        let input = r#"
+            agent Watcher {
+              goal: priority > 0.5
+              if context includes ["alert"] and score >= threshold {
+                print "go"
+              }
+            }
+        "#;
+        let mut lexer = Lexer::new(input);
+        let mut parser = Parser::new(&mut lexer);
+        let (program, errors) = parser.parse_program();
+
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
+        match &program.statements[0].node {
+            Statement::AgentDeclaration { body, .. } => {
+                assert!(body.iter().any(|s| matches!(
+                    &s.node,
+                    Statement::Goal(Expr::Binary(BinaryOp::Gt, _, _))
+                )));
+                assert!(body.iter().any(|s| matches!(
+                    &s.node,
+                    Statement::IfContextIncludes {
+                        condition: Some(Expr::Binary(BinaryOp::Gte, _, _)),
+                        ..
+                    }
+                )));
+            }
+            other => panic!("expected AgentDeclaration, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_match_with_metavariable_patterns() {
+        let input = r#"
+            agent Reminder {
+              on input(msg) {
+                match msg {
+                  when "remind me to $task at $time" {
+                    print "scheduled"
+                  }
+                  when "$anything" {
+                    print "fallback"
+                  }
+                }
+              }
+            }
+        "#;
+        let mut lexer = Lexer::new(input);
+        let mut parser = Parser::new(&mut lexer);
+        let (program, errors) = parser.parse_program();
+
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
+        match &program.statements[0].node {
+            Statement::AgentDeclaration { body, .. } => {
+                let on_input = body
+                    .iter()
+                    .find_map(|s| match &s.node {
+                        Statement::OnInput { body, .. } => Some(body),
+                        _ => None,
+                    })
+                    .expect("expected OnInput");
+                match &on_input[0].node {
+                    Statement::Match { target, arms } => {
+                        assert_eq!(target, &Expr::Ident("msg".to_string()));
+                        assert_eq!(arms.len(), 2);
+                        assert_eq!(
+                            arms[0].pattern.segments,
+                            vec![
+                                PatternSegment::Literal("remind".to_string()),
+                                PatternSegment::Literal("me".to_string()),
+                                PatternSegment::Literal("to".to_string()),
+                                PatternSegment::Var("task".to_string()),
+                                PatternSegment::Literal("at".to_string()),
+                                PatternSegment::Var("time".to_string()),
+                            ]
+                        );
+                    }
+                    other => panic!("expected Match, got {:?}", other),
+                }
+            }
+            other => panic!("expected AgentDeclaration, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_single_line_train_and_recall() {
+        let input = r#"
+            train msg -> "greeting"
+            recall msg -> mem.reply
+        "#;
+        let mut lexer = Lexer::new(input);
+        let mut parser = Parser::new(&mut lexer);
+        let (program, errors) = parser.parse_program();
+
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
+        assert_eq!(program.statements.len(), 2);
+        match &program.statements[0].node {
+            Statement::TrainAssociation { input, label } => {
+                assert_eq!(input, &Expr::Ident("msg".to_string()));
+                assert_eq!(label, "greeting");
+            }
+            other => panic!("expected TrainAssociation, got {:?}", other),
+        }
+        match &program.statements[1].node {
+            Statement::Recall { source, target } => {
+                assert_eq!(source, "msg");
+                assert_eq!(target, "mem.reply");
+            }
+            other => panic!("expected Recall, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_import() {
+        let input = "import greeter";
+        let mut lexer = Lexer::new(input);
+        let mut parser = Parser::new(&mut lexer);
+        let (program, errors) = parser.parse_program();
+
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
+        match &program.statements[0].node {
+            Statement::Import { name } => assert_eq!(name, "greeter"),
+            other => panic!("expected Import, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_enter() {
+        let input = "enter Greeter";
+        let mut lexer = Lexer::new(input);
+        let mut parser = Parser::new(&mut lexer);
+        let (program, errors) = parser.parse_program();
+
+        assert!(errors.is_empty(), "unexpected parse errors: {:?}", errors);
+        match &program.statements[0].node {
+            Statement::Enter { name } => assert_eq!(name, "Greeter"),
+            other => panic!("expected Enter, got {:?}", other),
+        }
+    }
 }
@@ -4,7 +4,7 @@ use pyo3::types::{PyDict, PyList};
 use pyo3::wrap_pyfunction;
 
 use crate::sentience_core::{
-    ast::{SentienceTokenAst, ThoughtType, Value},
+    ast::{Program, SentienceTokenAst, ThoughtType, Value},
     runtime::{ExecutionResult, SimpleRuntime},
     SentienceCore,
 };
@@ -24,13 +24,13 @@ impl PySentienceCore {
         Self { core }
     }
 
-    /// Parse Sentience DSL into AST
-    fn parse(&self, src: &str) -> PyResult<PySentienceTokenAst> {
-        let ast = self
+    /// Parse Sentience DSL into a program (every top-level statement as its own AST)
+    fn parse(&self, src: &str) -> PyResult<PyProgram> {
+        let program = self
             .core
             .parse(src)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e))?;
-        Ok(PySentienceTokenAst { ast })
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(PyProgram { program })
     }
 
     /// Canonicalize AST for deterministic processing
@@ -49,25 +49,62 @@ impl PySentienceCore {
         self.core.embed(&canon.ast)
     }
 
-    /// Execute AST and return results
-    fn execute(&mut self, ast: &PySentienceTokenAst) -> PyResult<PyExecutionResult> {
+    /// Execute every statement in a program and return results
+    fn execute(&mut self, program: &PyProgram) -> PyResult<PyExecutionResult> {
         let result = self
             .core
-            .execute(&ast.ast)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e))?;
+            .execute(&program.program)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
         Ok(PyExecutionResult { result })
     }
 
-    /// Complete pipeline: parse → canonicalize → hash → embed → execute
+    /// Complete pipeline: parse → canonicalize → analyze → hash → embed → execute
     fn process_step(&mut self, src: &str) -> PyResult<PyExecutionResult> {
-        let result = self
-            .core
-            .process_step(src)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e))?;
+        let result = self.core.process_step(src).map_err(|errors| {
+            let message = errors
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(message)
+        })?;
         Ok(PyExecutionResult { result })
     }
 }
 
+/// Python wrapper for a parsed Program (every top-level statement as its own AST)
+#[pyclass]
+pub struct PyProgram {
+    program: Program,
+}
+
+#[pymethods]
+impl PyProgram {
+    /// Number of top-level statements
+    fn len(&self) -> usize {
+        self.program.statements.len()
+    }
+
+    /// Get the AST for statement `index`
+    fn statement(&self, index: usize) -> Option<PySentienceTokenAst> {
+        self.program
+            .statements
+            .get(index)
+            .cloned()
+            .map(|ast| PySentienceTokenAst { ast })
+    }
+
+    /// All statements as a list of ASTs
+    fn statements(&self) -> Vec<PySentienceTokenAst> {
+        self.program
+            .statements
+            .iter()
+            .cloned()
+            .map(|ast| PySentienceTokenAst { ast })
+            .collect()
+    }
+}
+
 /// Python wrapper for SentienceTokenAst
 #[pyclass]
 pub struct PySentienceTokenAst {
@@ -229,6 +266,7 @@ fn create_sentience_core() -> PySentienceCore {
 #[pymodule]
 fn sentience_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PySentienceCore>()?;
+    m.add_class::<PyProgram>()?;
     m.add_class::<PySentienceTokenAst>()?;
     m.add_class::<PyExecutionResult>()?;
     m.add_function(wrap_pyfunction!(create_sentience_core, m)?)?;
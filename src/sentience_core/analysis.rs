@@ -0,0 +1,193 @@
+//! Semantic analysis: folds an untyped [`Program`] into a [`TypedProgram`], the way a
+//! `Ast -> Result<TypedAst, Vec<SentienceError>>` pass would in a typed-AST pipeline. Runs
+//! between `canonicalize` and `execute` so a program with a missing field or a dangling
+//! `concept.from` reference is rejected up front, with every problem reported at once, instead of
+//! producing a silently-wrong token or a dangling `DerivedFrom` edge at execution time.
+
+use crate::sentience_core::ast::{Program, SentienceTokenAst, ThoughtType, Value};
+use crate::sentience_core::error::SentienceError;
+use crate::sentience_core::runtime::Cortex;
+
+/// A [`SentienceTokenAst`] that has passed semantic analysis, annotated with the token IDs its
+/// `from` references (if any) resolved to.
+#[derive(Clone, Debug)]
+pub struct TypedAst {
+    pub ast: SentienceTokenAst,
+    pub resolved_from: Vec<String>,
+}
+
+/// A [`Program`] whose statements have all passed semantic analysis.
+#[derive(Clone, Debug)]
+pub struct TypedProgram {
+    pub statements: Vec<TypedAst>,
+}
+
+/// The fields each [`ThoughtType`] must carry to be executable.
+fn required_fields(ttype: &ThoughtType) -> &'static [&'static str] {
+    match ttype {
+        ThoughtType::Percept => &["modality", "content"],
+        ThoughtType::Reflection => &["ops"],
+        ThoughtType::Action => &["name"],
+        ThoughtType::SelfModel => &["name"],
+        ThoughtType::Concept => &["summary"],
+        _ => &[],
+    }
+}
+
+/// Fold `program` into a [`TypedProgram`], collecting every error across every statement instead
+/// of stopping at the first one.
+pub fn analyze(program: &Program, cortex: &dyn Cortex) -> Result<TypedProgram, Vec<SentienceError>> {
+    let mut statements = Vec::new();
+    let mut errors = Vec::new();
+
+    for ast in &program.statements {
+        match analyze_statement(ast, cortex) {
+            Ok(typed) => statements.push(typed),
+            Err(mut stmt_errors) => errors.append(&mut stmt_errors),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(TypedProgram { statements })
+    } else {
+        Err(errors)
+    }
+}
+
+fn analyze_statement(
+    ast: &SentienceTokenAst,
+    cortex: &dyn Cortex,
+) -> Result<TypedAst, Vec<SentienceError>> {
+    let mut errors = Vec::new();
+
+    for field in required_fields(&ast.ttype) {
+        if ast.get_field(field).is_none() {
+            errors.push(SentienceError::new(
+                "E_MISSING_FIELD",
+                format!("{} requires a '{}' field", ast.ttype, field),
+                ast.span.clone(),
+            ));
+        }
+    }
+
+    let resolved_from = resolve_from(ast, cortex, &mut errors);
+
+    if errors.is_empty() {
+        Ok(TypedAst {
+            ast: ast.clone(),
+            resolved_from,
+        })
+    } else {
+        Err(errors)
+    }
+}
+
+/// Resolve a `concept`'s `from` references against `cortex`, reporting any ID that doesn't name a
+/// committed token instead of letting it become a dangling `DerivedFrom` edge.
+fn resolve_from(
+    ast: &SentienceTokenAst,
+    cortex: &dyn Cortex,
+    errors: &mut Vec<SentienceError>,
+) -> Vec<String> {
+    let mut resolved = Vec::new();
+    let Some(Value::List(items)) = ast.get_field("from") else {
+        return resolved;
+    };
+
+    for item in items {
+        let Value::Str(id) = item else { continue };
+        if cortex.get_token(id).is_some() {
+            resolved.push(id.clone());
+        } else {
+            errors.push(SentienceError::new(
+                "E_UNKNOWN_TOKEN_REF",
+                format!("concept references unknown token '{}'", id),
+                ast.span.clone(),
+            ));
+        }
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentience_core::ast::{Provenance, SentienceToken, Span, TokenMeta};
+    use crate::sentience_core::runtime::InMemoryCortex;
+
+    fn committed_token(id: &str) -> SentienceToken {
+        SentienceToken::new(
+            id.to_string(),
+            SentienceTokenAst::new(ThoughtType::Percept, Span::new(1, 1, 1, 1))
+                .with_field("modality".to_string(), Value::Str("text".to_string()))
+                .with_field("content".to_string(), Value::Str("hi".to_string())),
+            vec![0.0; 4],
+            Provenance {
+                stm_ids: Vec::new(),
+                refnet_id: "test".to_string(),
+                rules_applied: Vec::new(),
+                agent_id: "test".to_string(),
+                step_id: 0,
+                timestamp: 0,
+            },
+            TokenMeta {
+                version: "test".to_string(),
+                strength: 1.0,
+                belief: 1.0,
+                tags: Vec::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn rejects_a_percept_missing_its_content_field() {
+        let ast =
+            SentienceTokenAst::new(ThoughtType::Percept, Span::new(1, 1, 1, 1))
+                .with_field("modality".to_string(), Value::Str("text".to_string()));
+        let program = Program {
+            statements: vec![ast],
+        };
+        let cortex = InMemoryCortex::new(64);
+        let errors = analyze(&program, &cortex).unwrap_err();
+        assert!(errors.iter().any(|e| e.code == "E_MISSING_FIELD"));
+    }
+
+    #[test]
+    fn rejects_a_concept_that_references_an_unknown_token() {
+        let ast = SentienceTokenAst::new(ThoughtType::Concept, Span::new(1, 1, 1, 1))
+            .with_field("summary".to_string(), Value::Str("s".to_string()))
+            .with_field(
+                "from".to_string(),
+                Value::List(vec![Value::Str("does_not_exist".to_string())]),
+            );
+        let program = Program {
+            statements: vec![ast],
+        };
+        let cortex = InMemoryCortex::new(64);
+        let errors = analyze(&program, &cortex).unwrap_err();
+        assert!(errors.iter().any(|e| e.code == "E_UNKNOWN_TOKEN_REF"));
+    }
+
+    #[test]
+    fn resolves_a_concept_whose_from_references_exist() {
+        let mut cortex = InMemoryCortex::new(64);
+        // `commit` assigns the token its real content-hash id, so the placeholder passed into
+        // `committed_token` isn't what ends up in the Cortex — use the id `commit` hands back.
+        let committed_id = cortex
+            .commit(&committed_token("tok_1"), &[])
+            .expect("commit should succeed");
+
+        let ast = SentienceTokenAst::new(ThoughtType::Concept, Span::new(1, 1, 1, 1))
+            .with_field("summary".to_string(), Value::Str("s".to_string()))
+            .with_field(
+                "from".to_string(),
+                Value::List(vec![Value::Str(committed_id.clone())]),
+            );
+        let program = Program {
+            statements: vec![ast],
+        };
+        let typed = analyze(&program, &cortex).unwrap();
+        assert_eq!(typed.statements[0].resolved_from, vec![committed_id]);
+    }
+}
@@ -32,6 +32,13 @@ impl fmt::Display for ThoughtType {
     }
 }
 
+/// A full parsed program: every top-level statement (`agent`, `embed`, `reflect { ... }`,
+/// `concept { ... }`, `action { ... }`, `self_model { ... }`) as its own [`SentienceTokenAst`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Program {
+    pub statements: Vec<SentienceTokenAst>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SentienceTokenAst {
     pub ttype: ThoughtType,
@@ -256,5 +263,5 @@ fn hash_string(s: &str) -> String {
 
     let mut hasher = DefaultHasher::new();
     s.hash(&mut hasher);
-    format!("{:x}", hasher.finish())[..16].to_string()
+    format!("{:016x}", hasher.finish())
 }
@@ -0,0 +1,111 @@
+//! Provenance-semiring belief aggregation for derived tokens.
+//!
+//! A derived `Concept`'s belief shouldn't be a constant — it should reflect how strongly its
+//! sources support it, the way differentiable Datalog-style reasoning tracks provenance through a
+//! semiring instead of a single boolean. A single derivation path (one source token, reached
+//! through one `DerivedFrom` edge) combines its source's belief with the edge's weight via the
+//! semiring's conjunction (`times`, ⊗); independent paths supporting the same concept combine via
+//! its disjunction (`plus`, ⊕). [`Semiring`] is pluggable so callers can swap in max-min or
+//! top-k-proof semantics instead of the default probabilistic (product / noisy-OR) one.
+
+/// `zero`/`plus` form a commutative monoid for combining independent derivation paths; `one`/
+/// `times` form one for combining the steps within a single path.
+pub trait Semiring {
+    /// Identity for `plus`: "no support at all".
+    fn zero(&self) -> f32;
+    /// Identity for `times`: "fully supported, no attenuation".
+    fn one(&self) -> f32;
+    /// ⊕ — combine two independent derivation paths.
+    fn plus(&self, a: f32, b: f32) -> f32;
+    /// ⊗ — combine the steps of a single derivation path.
+    fn times(&self, a: f32, b: f32) -> f32;
+}
+
+/// The default semiring: product for conjunction and noisy-OR for disjunction, so belief is
+/// treated as an independent probability at every step.
+pub struct ProbabilisticSemiring;
+
+impl Semiring for ProbabilisticSemiring {
+    fn zero(&self) -> f32 {
+        0.0
+    }
+
+    fn one(&self) -> f32 {
+        1.0
+    }
+
+    fn plus(&self, a: f32, b: f32) -> f32 {
+        1.0 - (1.0 - a) * (1.0 - b)
+    }
+
+    fn times(&self, a: f32, b: f32) -> f32 {
+        a * b
+    }
+}
+
+/// One path deriving a concept from a single source: the source token's own belief, and the
+/// weight of the `DerivedFrom` edge connecting it to the concept.
+pub struct DerivationPath {
+    pub source_belief: f32,
+    pub edge_weight: f32,
+}
+
+/// Aggregate every derivation path supporting one derived token into a single belief in `[0, 1]`:
+/// each path's belief is `semiring.times(source_belief, edge_weight)`, and paths combine via
+/// `semiring.plus`. Callers are expected to have already deduplicated `paths` by source so the
+/// same source visited twice (e.g. through a cycle) doesn't count twice. Returns `semiring.zero()`
+/// for a concept with no surviving sources.
+pub fn aggregate_belief(paths: &[DerivationPath], semiring: &dyn Semiring) -> f32 {
+    let combined = paths.iter().fold(semiring.zero(), |acc, path| {
+        let path_belief = semiring.times(path.source_belief, path.edge_weight);
+        semiring.plus(acc, path_belief)
+    });
+    combined.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_single_path_is_just_its_own_conjunction() {
+        let paths = [DerivationPath {
+            source_belief: 0.5,
+            edge_weight: 0.8,
+        }];
+        let belief = aggregate_belief(&paths, &ProbabilisticSemiring);
+        assert!((belief - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn independent_paths_combine_via_noisy_or() {
+        let paths = [
+            DerivationPath {
+                source_belief: 0.5,
+                edge_weight: 1.0,
+            },
+            DerivationPath {
+                source_belief: 0.5,
+                edge_weight: 1.0,
+            },
+        ];
+        // 1 - (1 - 0.5)(1 - 0.5) = 0.75
+        let belief = aggregate_belief(&paths, &ProbabilisticSemiring);
+        assert!((belief - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn no_paths_yields_zero_belief() {
+        assert_eq!(aggregate_belief(&[], &ProbabilisticSemiring), 0.0);
+    }
+
+    #[test]
+    fn result_is_always_clamped_to_the_unit_interval() {
+        let paths = [DerivationPath {
+            source_belief: 2.0,
+            edge_weight: 2.0,
+        }];
+        let belief = aggregate_belief(&paths, &ProbabilisticSemiring);
+        assert!((0.0..=1.0).contains(&belief));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::sentience_core::ast::Span;
+use std::fmt;
+
+/// A span-carrying error from parsing or executing a Sentience Core program: an error code, a
+/// human-readable message, the primary span, and any secondary spans pointing at related source
+/// locations (e.g. where a block was opened, for an unclosed-brace error).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SentienceError {
+    pub code: String,
+    pub message: String,
+    pub span: Span,
+    pub labels: Vec<(Span, String)>,
+}
+
+impl SentienceError {
+    pub fn new(code: impl Into<String>, message: impl Into<String>, span: Span) -> Self {
+        SentienceError {
+            code: code.into(),
+            message: message.into(),
+            span,
+            labels: Vec::new(),
+        }
+    }
+
+    /// Attach a secondary span with its own label, e.g. pointing back at an opening brace.
+    pub fn with_label(mut self, span: Span, label: impl Into<String>) -> Self {
+        self.labels.push((span, label.into()));
+        self
+    }
+
+    /// Render this error as a caret-underlined snippet of `source`: the offending line, a `^^^^`
+    /// under the span columns, the message, then one indented line per secondary label.
+    pub fn render(&self, source: &str) -> String {
+        let mut out = format!(
+            "error[{}]: {}\n  --> line {}:{}\n{}",
+            self.code,
+            self.message,
+            self.span.line,
+            self.span.col,
+            render_span(source, &self.span)
+        );
+        for (span, label) in &self.labels {
+            out.push_str(&format!(
+                "\n  note: {} (line {}:{})\n{}",
+                label,
+                span.line,
+                span.col,
+                render_span(source, span)
+            ));
+        }
+        out
+    }
+}
+
+fn render_span(source: &str, span: &Span) -> String {
+    let line_text = source
+        .lines()
+        .nth(span.line.saturating_sub(1))
+        .unwrap_or("");
+    let underline_len = span.end_col.saturating_sub(span.col).max(1);
+    let pointer = format!(
+        "{}{}",
+        " ".repeat(span.col.saturating_sub(1)),
+        "^".repeat(underline_len)
+    );
+    format!("{}\n{}", line_text, pointer)
+}
+
+impl fmt::Display for SentienceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error[{}] at line {}:{}: {}",
+            self.code, self.span.line, self.span.col, self.message
+        )
+    }
+}
+
+impl std::error::Error for SentienceError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_a_caret_under_the_span() {
+        let err = SentienceError::new("E001", "unexpected token", Span::new(1, 7, 1, 8));
+        let rendered = err.render("agent 123");
+        assert!(rendered.contains("error[E001]: unexpected token"));
+        assert!(rendered.contains("agent 123"));
+        assert!(rendered.contains("      ^"));
+    }
+
+    #[test]
+    fn renders_secondary_labels() {
+        let err = SentienceError::new("E002", "unclosed block", Span::new(2, 1, 2, 2))
+            .with_label(Span::new(1, 8, 1, 9), "block opened here");
+        let rendered = err.render("reflect {\nrecall");
+        assert!(rendered.contains("note: block opened here"));
+    }
+}
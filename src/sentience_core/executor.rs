@@ -1,12 +1,23 @@
 use crate::sentience_core::ast::*;
+use crate::sentience_core::belief::{aggregate_belief, DerivationPath, ProbabilisticSemiring};
+use crate::sentience_core::error::SentienceError;
+use crate::sentience_core::hasher::token_hash;
 use crate::sentience_core::runtime::*;
+use std::collections::HashSet;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-/// Execute Sentience AST against runtime
-pub fn execute(
+/// Run one full cognitive cycle: pull the recent STM, evaluate it with RefNet, produce tokens
+/// for `ast`, and gate every token (new or previously deferred) through the Superego before
+/// committing it to the Cortex.
+///
+/// `pending` is the caller's deferred-token queue: tokens the Superego asked to revisit are
+/// pushed here instead of being dropped, and are re-judged at the start of the next cycle before
+/// `ast` itself is executed.
+pub fn cognitive_cycle(
     ast: &SentienceTokenAst,
     runtime: &mut dyn Runtime,
-) -> Result<ExecutionResult, String> {
+    pending: &mut Vec<SentienceToken>,
+) -> Result<ExecutionResult, SentienceError> {
     let mut result = ExecutionResult::new();
 
     // Get STM window for RefNet evaluation
@@ -16,44 +27,110 @@ pub fn execute(
     let metrics = runtime.refnet().evaluate(&stm);
     result.metrics = Some(metrics.clone());
 
+    // Retry anything the Superego deferred last cycle before producing new tokens.
+    let retrying: Vec<SentienceToken> = std::mem::take(pending);
+    for token in retrying {
+        gate_token(token, runtime, &metrics, &mut result, pending)?;
+    }
+
     // Execute based on token type
     match ast.ttype {
         ThoughtType::Percept => execute_percept(ast, &mut result)?,
         ThoughtType::Reflection => execute_reflection(ast, &mut result, &metrics)?,
         ThoughtType::Action => execute_action(ast, &mut result)?,
-        ThoughtType::Concept => execute_concept(ast, &mut result)?,
+        ThoughtType::Concept => execute_concept(ast, runtime, &mut result)?,
         ThoughtType::SelfModel => execute_self_model(ast, &mut result)?,
-        _ => return Err(format!("Unsupported token type: {:?}", ast.ttype)),
+        _ => {
+            return Err(SentienceError::new(
+                "E_UNSUPPORTED_TOKEN",
+                format!("unsupported token type: {}", ast.ttype),
+                ast.span.clone(),
+            ))
+        }
     }
 
-    // Apply Superego gating
-    for token in &mut result.tokens {
-        let verdict = runtime.superego().judge(token, &metrics);
-        match verdict {
-            Verdict::Allow => {
-                // Commit to Cortex
-                let commit_id = runtime.cortex().commit(token, &result.edges)?;
-                token.id = commit_id;
-            }
-            Verdict::Modify(modified_token) => {
-                *token = modified_token;
-                let commit_id = runtime.cortex().commit(token, &result.edges)?;
-                token.id = commit_id;
-            }
-            Verdict::Defer => {
-                // Skip this token for now
-                continue;
-            }
-            Verdict::Block(reason) => {
-                return Err(format!("Token blocked by Superego: {}", reason));
-            }
-        }
+    // Apply Superego gating to the tokens `ast` just produced.
+    let fresh: Vec<SentienceToken> = std::mem::take(&mut result.tokens);
+    for token in fresh {
+        gate_token(token, runtime, &metrics, &mut result, pending)?;
     }
+    result.token_id = result.tokens.last().map(|token| token.id.clone());
+    result.embedding = result.tokens.last().map(|token| token.embedding.clone());
+
+    maybe_consolidate(runtime, &metrics, &mut result);
 
     Ok(result)
 }
 
-fn execute_percept(ast: &SentienceTokenAst, result: &mut ExecutionResult) -> Result<(), String> {
+/// Judge a single token against the Superego and act on the verdict: commit (`Allow`/`Modify`),
+/// requeue (`Defer`), or drop it while recording why (`Block`).
+fn gate_token(
+    mut token: SentienceToken,
+    runtime: &mut dyn Runtime,
+    metrics: &RefMetrics,
+    result: &mut ExecutionResult,
+    pending: &mut Vec<SentienceToken>,
+) -> Result<(), SentienceError> {
+    let verdict = runtime.superego().judge(&token, metrics);
+    match verdict {
+        Verdict::Allow => {
+            let span = token.ast.span.clone();
+            let commit_id = runtime
+                .cortex()
+                .commit(&token, &result.edges)
+                .map_err(|e| SentienceError::new("E_COMMIT", e, span))?;
+            token.id = commit_id;
+            result.tokens.push(token);
+        }
+        Verdict::Modify(modified_token) => {
+            let mut modified_token = modified_token;
+            let span = modified_token.ast.span.clone();
+            let commit_id = runtime
+                .cortex()
+                .commit(&modified_token, &result.edges)
+                .map_err(|e| SentienceError::new("E_COMMIT", e, span))?;
+            modified_token.id = commit_id;
+            result.tokens.push(modified_token);
+        }
+        Verdict::Defer => {
+            pending.push(token);
+        }
+        Verdict::Block(reason) => {
+            result.blocked.push(reason);
+        }
+    }
+    Ok(())
+}
+
+/// If RefNet's evaluation points at consolidation (either as the chosen `next_action` or as the
+/// highest-scoring entry in `action_logits`), pull a small recall window around the most
+/// recently committed token so the result carries what it would be consolidated against.
+fn maybe_consolidate(
+    runtime: &mut dyn Runtime,
+    metrics: &RefMetrics,
+    result: &mut ExecutionResult,
+) {
+    let wants_consolidation = metrics.next_action == "consolidate"
+        || metrics
+            .action_logits
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|(action, _)| action == "consolidate")
+            .unwrap_or(false);
+
+    if !wants_consolidation {
+        return;
+    }
+
+    if let Some(anchor) = result.tokens.last() {
+        result.consolidated = runtime.cortex().recall_similar(&anchor.embedding, 5);
+    }
+}
+
+fn execute_percept(
+    ast: &SentienceTokenAst,
+    result: &mut ExecutionResult,
+) -> Result<(), SentienceError> {
     let modality = ast.get_field_str("modality").unwrap_or("unknown");
     let content = ast.get_field_str("content").unwrap_or("");
 
@@ -67,7 +144,7 @@ fn execute_reflection(
     ast: &SentienceTokenAst,
     result: &mut ExecutionResult,
     metrics: &RefMetrics,
-) -> Result<(), String> {
+) -> Result<(), SentienceError> {
     let empty_list = Vec::new();
     let ops = ast
         .get_field("ops")
@@ -92,7 +169,10 @@ fn execute_reflection(
     Ok(())
 }
 
-fn execute_action(ast: &SentienceTokenAst, result: &mut ExecutionResult) -> Result<(), String> {
+fn execute_action(
+    ast: &SentienceTokenAst,
+    result: &mut ExecutionResult,
+) -> Result<(), SentienceError> {
     let action_name = ast.get_field_str("name").unwrap_or("unknown");
     let target = ast.get_field_str("target").unwrap_or("");
 
@@ -102,7 +182,10 @@ fn execute_action(ast: &SentienceTokenAst, result: &mut ExecutionResult) -> Resu
     Ok(())
 }
 
-fn execute_self_model(ast: &SentienceTokenAst, result: &mut ExecutionResult) -> Result<(), String> {
+fn execute_self_model(
+    ast: &SentienceTokenAst,
+    result: &mut ExecutionResult,
+) -> Result<(), SentienceError> {
     let name = ast.get_field_str("name").unwrap_or("unknown");
 
     let token = create_self_model_token(name, &ast.span)?;
@@ -111,7 +194,11 @@ fn execute_self_model(ast: &SentienceTokenAst, result: &mut ExecutionResult) ->
     Ok(())
 }
 
-fn execute_concept(ast: &SentienceTokenAst, result: &mut ExecutionResult) -> Result<(), String> {
+fn execute_concept(
+    ast: &SentienceTokenAst,
+    runtime: &mut dyn Runtime,
+    result: &mut ExecutionResult,
+) -> Result<(), SentienceError> {
     let summary = ast.get_field_str("summary").unwrap_or("");
     let empty_list = Vec::new();
     let from_tokens = ast
@@ -122,25 +209,50 @@ fn execute_concept(ast: &SentienceTokenAst, result: &mut ExecutionResult) -> Res
         })
         .unwrap_or(&empty_list);
 
+    // Dedup so a source repeated in `from` (or reached again via a cycle) contributes only one
+    // derivation path instead of being double-counted by the belief aggregate below.
     let mut source_ids = Vec::new();
+    let mut seen = HashSet::new();
     for token_ref in from_tokens {
         if let Value::Str(id) = token_ref {
-            source_ids.push(id.clone());
+            if seen.insert(id.clone()) {
+                source_ids.push(id.clone());
+            }
         }
     }
 
-    let token = create_concept_token(summary, source_ids, &ast.span)?;
+    let cortex = runtime.cortex();
+    // A source's `strength` doubles as its edge weight: how strongly it carries over into
+    // whatever is derived from it, combined (⊗) with its own belief to make one derivation path.
+    let paths: Vec<DerivationPath> = source_ids
+        .iter()
+        .filter_map(|id| cortex.get_token(id))
+        .map(|source| DerivationPath {
+            source_belief: source.meta.belief,
+            edge_weight: source.meta.strength,
+        })
+        .collect();
+    let belief = aggregate_belief(&paths, &ProbabilisticSemiring);
+
+    let mut token = create_concept_token(summary, source_ids, belief, &ast.span)?;
+    // The id `commit` will store this concept under, so the edges below point at a real token.
+    token.id = token_hash(&token.ast);
     result.tokens.push(token.clone());
 
-    // Generate DERIVED_FROM edges
+    // Generate DERIVED_FROM edges, each weighted by its source's strength — the same weight
+    // folded into the concept's aggregate belief above.
     if let Some(Value::List(from_list)) = token.ast.get_field("from") {
         for source_id in from_list {
             if let Value::Str(src_id) = source_id {
+                let weight = cortex
+                    .get_token(src_id)
+                    .map(|source| source.meta.strength)
+                    .unwrap_or(0.0);
                 let edge = Edge::new(
                     src_id.clone(),
                     token.id.clone(),
                     EdgeType::DerivedFrom,
-                    1.0,
+                    weight,
                     current_timestamp(),
                 );
                 result.edges.push(edge);
@@ -152,7 +264,7 @@ fn execute_concept(ast: &SentienceTokenAst, result: &mut ExecutionResult) -> Res
 }
 
 // Token creation helpers
-fn create_self_model_token(name: &str, span: &Span) -> Result<SentienceToken, String> {
+fn create_self_model_token(name: &str, span: &Span) -> Result<SentienceToken, SentienceError> {
     let ast = SentienceTokenAst::new(ThoughtType::SelfModel, span.clone())
         .with_field("name".to_string(), Value::Str(name.to_string()));
 
@@ -178,7 +290,7 @@ fn create_percept_token(
     modality: &str,
     content: &str,
     span: &Span,
-) -> Result<SentienceToken, String> {
+) -> Result<SentienceToken, SentienceError> {
     let ast = SentienceTokenAst::new(ThoughtType::Percept, span.clone())
         .with_field("modality".to_string(), Value::Str(modality.to_string()))
         .with_field("content".to_string(), Value::Str(content.to_string()));
@@ -205,7 +317,7 @@ fn create_reflection_token(
     ops: Vec<String>,
     metrics: &RefMetrics,
     span: &Span,
-) -> Result<SentienceToken, String> {
+) -> Result<SentienceToken, SentienceError> {
     let ast = SentienceTokenAst::new(ThoughtType::Reflection, span.clone()).with_field(
         "ops".to_string(),
         Value::List(ops.into_iter().map(Value::Str).collect()),
@@ -229,7 +341,11 @@ fn create_reflection_token(
     ))
 }
 
-fn create_action_token(name: &str, target: &str, span: &Span) -> Result<SentienceToken, String> {
+fn create_action_token(
+    name: &str,
+    target: &str,
+    span: &Span,
+) -> Result<SentienceToken, SentienceError> {
     let ast = SentienceTokenAst::new(ThoughtType::Action, span.clone())
         .with_field("name".to_string(), Value::Str(name.to_string()))
         .with_field("target".to_string(), Value::Str(target.to_string()));
@@ -255,8 +371,9 @@ fn create_action_token(name: &str, target: &str, span: &Span) -> Result<Sentienc
 fn create_concept_token(
     summary: &str,
     from_tokens: Vec<String>,
+    belief: f32,
     span: &Span,
-) -> Result<SentienceToken, String> {
+) -> Result<SentienceToken, SentienceError> {
     let ast = SentienceTokenAst::new(ThoughtType::Concept, span.clone())
         .with_field("summary".to_string(), Value::Str(summary.to_string()))
         .with_field(
@@ -268,8 +385,8 @@ fn create_concept_token(
     let provenance = create_provenance();
     let meta = TokenMeta {
         version: "sentience/0.2".to_string(),
-        strength: 0.8,
-        belief: 0.8,
+        strength: belief,
+        belief,
         tags: vec!["concept".to_string()],
     };
 
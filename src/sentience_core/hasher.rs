@@ -1,22 +1,26 @@
 use crate::sentience_core::ast::SentienceTokenAst;
+use crate::sentience_core::ids::encode_id;
 use serde_json;
 use sha2::{Digest, Sha256};
 
+/// Schema tag embedded in every content hash, bumped whenever the hashed shape changes.
+pub const SCHEMA_VERSION: &str = "sentience/0.2";
+
 /// Generate deterministic token ID from canonical AST
 pub fn token_hash(canon_ast: &SentienceTokenAst) -> String {
     // Convert to canonical JSON
     let json = serde_json::to_string(canon_ast).expect("Failed to serialize AST to JSON");
 
     // Add schema version for future compatibility
-    let versioned_json = format!("{{\"schema\":\"sentience/0.2\",\"ast\":{}}}", json);
+    let versioned_json = format!("{{\"schema\":\"{}\",\"ast\":{}}}", SCHEMA_VERSION, json);
 
     // Generate SHA-256 hash
     let mut hasher = Sha256::new();
     hasher.update(versioned_json.as_bytes());
     let hash_bytes = hasher.finalize();
 
-    // Return shortened hex ID with prefix
-    format!("mem_{}", hex::encode(&hash_bytes[..8]))
+    // Checksummed, base58-encoded ID with prefix so a typo doesn't silently resolve elsewhere
+    encode_id("mem", &hash_bytes[..8])
 }
 
 /// Generate edge ID from source, relation, and target
@@ -25,7 +29,7 @@ pub fn edge_hash(source_id: &str, edge_type: &str, target_id: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(input.as_bytes());
     let hash_bytes = hasher.finalize();
-    format!("edge_{}", hex::encode(&hash_bytes[..8]))
+    encode_id("edge", &hash_bytes[..8])
 }
 
 #[cfg(test)]
@@ -49,7 +53,7 @@ mod tests {
 
         assert_eq!(hash1, hash2);
         assert!(hash1.starts_with("mem_"));
-        assert_eq!(hash1.len(), 12); // "mem_" + 8 hex chars
+        assert!(crate::sentience_core::ids::decode_id(&hash1, "mem").is_ok());
     }
 
     #[test]
@@ -0,0 +1,97 @@
+//! Base58check-style encoding for the human-readable IDs `token_hash`/`edge_hash` hand out.
+//!
+//! Raw hex digests are easy to mistype or corrupt when they show up in saved files, logs, or a
+//! REPL recall command. `encode_id` appends a 4-byte checksum (the first 4 bytes of
+//! `SHA-256(SHA-256(payload))`) before base58-encoding the payload, so `decode_id` can reject a
+//! single flipped character instead of silently resolving to the wrong token.
+
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum IdError {
+    /// The id has no `prefix_` separator, or the body isn't valid base58, or it's shorter than
+    /// the 4-byte checksum.
+    Malformed,
+    /// The id's `prefix_` doesn't match what the caller expected (e.g. a `edge_...` id passed
+    /// where a `mem_...` token id was expected).
+    WrongPrefix,
+    /// The body decoded fine but its trailing 4 bytes don't match the checksum of the payload
+    /// that precedes them.
+    BadChecksum,
+}
+
+impl fmt::Display for IdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdError::Malformed => write!(f, "malformed id"),
+            IdError::WrongPrefix => write!(f, "id has the wrong prefix"),
+            IdError::BadChecksum => write!(f, "id checksum does not match its payload"),
+        }
+    }
+}
+
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    let once = Sha256::digest(payload);
+    let twice = Sha256::digest(once);
+    let mut out = [0u8; 4];
+    out.copy_from_slice(&twice[..4]);
+    out
+}
+
+/// Encode `payload` as `<prefix>_<base58(payload || checksum)>`.
+pub fn encode_id(prefix: &str, payload: &[u8]) -> String {
+    let mut buf = Vec::with_capacity(payload.len() + 4);
+    buf.extend_from_slice(payload);
+    buf.extend_from_slice(&checksum(payload));
+    format!("{}_{}", prefix, bs58::encode(buf).into_string())
+}
+
+/// Decode an id produced by [`encode_id`], verifying its prefix and checksum. Returns the
+/// original payload bytes on success.
+pub fn decode_id(id: &str, expected_prefix: &str) -> Result<Vec<u8>, IdError> {
+    let (prefix, body) = id.split_once('_').ok_or(IdError::Malformed)?;
+    if prefix != expected_prefix {
+        return Err(IdError::WrongPrefix);
+    }
+
+    let raw = bs58::decode(body).into_vec().map_err(|_| IdError::Malformed)?;
+    if raw.len() < 4 {
+        return Err(IdError::Malformed);
+    }
+    let (payload, sum) = raw.split_at(raw.len() - 4);
+    if checksum(payload) != sum {
+        return Err(IdError::BadChecksum);
+    }
+    Ok(payload.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let id = encode_id("mem", &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(id.starts_with("mem_"));
+        let decoded = decode_id(&id, "mem").unwrap();
+        assert_eq!(decoded, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn rejects_wrong_prefix() {
+        let id = encode_id("mem", &[1, 2, 3]);
+        assert_eq!(decode_id(&id, "edge"), Err(IdError::WrongPrefix));
+    }
+
+    #[test]
+    fn rejects_single_char_typo() {
+        let mut id = encode_id("mem", &[9, 9, 9, 9]);
+        let last = id.pop().unwrap();
+        // Swap the final character for another valid base58 character so the string still
+        // decodes as base58, but the checksum no longer matches.
+        let replacement = if last == '1' { '2' } else { '1' };
+        id.push(replacement);
+        assert_eq!(decode_id(&id, "mem"), Err(IdError::BadChecksum));
+    }
+}
@@ -0,0 +1,364 @@
+//! Pluggable vector index backing `Cortex::recall_similar`.
+//!
+//! `FlatIndex` is a brute-force O(n) scan, fine for small stores and tests. `HnswIndex` is an
+//! approximate nearest-neighbor index (Hierarchical Navigable Small World) that stays sub-linear
+//! as the token store grows.
+
+use rand::Rng;
+
+/// A nearest-neighbor index over `(id, embedding)` pairs, keyed by cosine distance
+/// (`1 - cosine_similarity`). Implementations may return approximate results.
+pub trait VectorIndex: Send + Sync {
+    fn insert(&mut self, id: String, vec: Vec<f32>);
+    /// Returns up to `k` `(id, distance)` pairs ordered by ascending distance.
+    fn query(&self, vec: &[f32], k: usize) -> Vec<(String, f32)>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot_product / (norm_a * norm_b)
+    }
+}
+
+/// `1 - cosine_similarity`, or `None` if the two vectors have mismatched dimensions.
+fn distance(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() {
+        return None;
+    }
+    Some(1.0 - cosine_similarity(a, b))
+}
+
+/// Brute-force fallback: scans every stored vector and sorts. O(n log n) per query.
+#[derive(Default)]
+pub struct FlatIndex {
+    entries: Vec<(String, Vec<f32>)>,
+}
+
+impl FlatIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl VectorIndex for FlatIndex {
+    fn insert(&mut self, id: String, vec: Vec<f32>) {
+        self.entries.push((id, vec));
+    }
+
+    fn query(&self, vec: &[f32], k: usize) -> Vec<(String, f32)> {
+        let mut scored: Vec<(String, f32)> = self
+            .entries
+            .iter()
+            .filter_map(|(id, v)| distance(vec, v).map(|d| (id.clone(), d)))
+            .collect();
+        scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        scored.truncate(k);
+        scored
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+pub struct HnswConfig {
+    /// Max bidirectional links per node above layer 0.
+    pub m: usize,
+    /// Max links at layer 0 (conventionally `2 * m`).
+    pub m0: usize,
+    /// Candidate list size used while inserting.
+    pub ef_construction: usize,
+    /// Candidate list size used while querying.
+    pub ef_search: usize,
+    /// Level-generation scale, conventionally `1 / ln(m)`.
+    pub ml: f64,
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        let m = 16;
+        HnswConfig {
+            m,
+            m0: m * 2,
+            ef_construction: 200,
+            ef_search: 50,
+            ml: 1.0 / (m as f64).ln(),
+        }
+    }
+}
+
+struct Node {
+    id: String,
+    vec: Vec<f32>,
+    /// `neighbors[layer]` holds the indices of this node's neighbors at that layer.
+    neighbors: Vec<Vec<usize>>,
+}
+
+/// Hierarchical Navigable Small World approximate nearest-neighbor index.
+pub struct HnswIndex {
+    config: HnswConfig,
+    nodes: Vec<Node>,
+    entry_point: Option<usize>,
+    max_layer: usize,
+}
+
+impl HnswIndex {
+    pub fn new(config: HnswConfig) -> Self {
+        HnswIndex {
+            config,
+            nodes: Vec::new(),
+            entry_point: None,
+            max_layer: 0,
+        }
+    }
+
+    fn random_layer(&self) -> usize {
+        let uniform: f64 = rand::thread_rng().gen_range(f64::EPSILON..1.0);
+        (-uniform.ln() * self.config.ml).floor() as usize
+    }
+
+    fn dist_to(&self, node_idx: usize, query: &[f32]) -> Option<f32> {
+        distance(query, &self.nodes[node_idx].vec)
+    }
+
+    /// Greedily walk from `from` towards `query`, staying on `layer`, until no neighbor improves
+    /// on the current closest node.
+    fn greedy_closest(&self, from: usize, query: &[f32], layer: usize) -> usize {
+        let mut current = from;
+        let mut current_dist = self.dist_to(current, query).unwrap_or(f32::MAX);
+        loop {
+            let mut improved = false;
+            if let Some(neighbors) = self.nodes[current].neighbors.get(layer) {
+                for &neighbor in neighbors {
+                    if let Some(d) = self.dist_to(neighbor, query) {
+                        if d < current_dist {
+                            current = neighbor;
+                            current_dist = d;
+                            improved = true;
+                        }
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+        current
+    }
+
+    /// Best-first search of `layer` starting from `entry`, returning up to `ef` closest nodes.
+    fn search_layer(&self, entry: usize, query: &[f32], layer: usize, ef: usize) -> Vec<(usize, f32)> {
+        use std::collections::HashSet;
+
+        let Some(entry_dist) = self.dist_to(entry, query) else {
+            return Vec::new();
+        };
+
+        let mut visited: HashSet<usize> = HashSet::new();
+        visited.insert(entry);
+
+        let mut candidates: Vec<(usize, f32)> = vec![(entry, entry_dist)];
+        let mut results: Vec<(usize, f32)> = vec![(entry, entry_dist)];
+
+        while let Some(pos) = candidates
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1 .1.partial_cmp(&b.1 .1).unwrap())
+            .map(|(i, _)| i)
+        {
+            let (current, current_dist) = candidates.remove(pos);
+            let worst_result = results
+                .iter()
+                .map(|(_, d)| *d)
+                .fold(f32::MIN, f32::max);
+            if results.len() >= ef && current_dist > worst_result {
+                break;
+            }
+
+            if let Some(neighbors) = self.nodes[current].neighbors.get(layer) {
+                for &neighbor in neighbors {
+                    if visited.contains(&neighbor) {
+                        continue;
+                    }
+                    visited.insert(neighbor);
+                    if let Some(d) = self.dist_to(neighbor, query) {
+                        candidates.push((neighbor, d));
+                        results.push((neighbor, d));
+                        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+                        if results.len() > ef {
+                            results.truncate(ef);
+                        }
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Select up to `cap` neighbors from `candidates`, closest-first, for `node_idx` at `layer`
+    /// and wire the connection bidirectionally, pruning the neighbor back down to its own cap.
+    fn connect(&mut self, node_idx: usize, mut candidates: Vec<(usize, f32)>, layer: usize, cap: usize) {
+        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        candidates.truncate(cap);
+
+        for &(neighbor, _) in &candidates {
+            self.ensure_layer(node_idx, layer);
+            self.nodes[node_idx].neighbors[layer].push(neighbor);
+
+            self.ensure_layer(neighbor, layer);
+            self.nodes[neighbor].neighbors[layer].push(node_idx);
+            self.prune_neighbor(neighbor, layer, cap);
+        }
+    }
+
+    fn ensure_layer(&mut self, node_idx: usize, layer: usize) {
+        let neighbors = &mut self.nodes[node_idx].neighbors;
+        while neighbors.len() <= layer {
+            neighbors.push(Vec::new());
+        }
+    }
+
+    /// Keep only the `cap` closest neighbors of `node_idx` at `layer`.
+    fn prune_neighbor(&mut self, node_idx: usize, layer: usize, cap: usize) {
+        let vec = self.nodes[node_idx].vec.clone();
+        let neighbors = &self.nodes[node_idx].neighbors[layer];
+        if neighbors.len() <= cap {
+            return;
+        }
+        let mut scored: Vec<(usize, f32)> = neighbors
+            .iter()
+            .filter_map(|&n| distance(&vec, &self.nodes[n].vec).map(|d| (n, d)))
+            .collect();
+        scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        scored.truncate(cap);
+        self.nodes[node_idx].neighbors[layer] = scored.into_iter().map(|(n, _)| n).collect();
+    }
+}
+
+impl VectorIndex for HnswIndex {
+    fn insert(&mut self, id: String, vec: Vec<f32>) {
+        let node_idx = self.nodes.len();
+        let layer = self.random_layer();
+        self.nodes.push(Node {
+            id,
+            vec: vec.clone(),
+            neighbors: Vec::new(),
+        });
+        self.ensure_layer(node_idx, layer);
+
+        let Some(mut entry) = self.entry_point else {
+            self.entry_point = Some(node_idx);
+            self.max_layer = layer;
+            return;
+        };
+
+        // Descend greedily from the top layer down to just above `layer`, keeping only the
+        // single closest node at each level.
+        for l in (layer + 1..=self.max_layer).rev() {
+            entry = self.greedy_closest(entry, &vec, l);
+        }
+
+        // From `min(layer, max_layer)` down to 0, run a best-first search and connect to the
+        // closest found neighbors.
+        for l in (0..=layer.min(self.max_layer)).rev() {
+            let found = self.search_layer(entry, &vec, l, self.config.ef_construction);
+            let cap = if l == 0 { self.config.m0 } else { self.config.m };
+            self.connect(node_idx, found.clone(), l, cap);
+            if let Some((closest, _)) = found.into_iter().next() {
+                entry = closest;
+            }
+        }
+
+        if layer > self.max_layer {
+            self.entry_point = Some(node_idx);
+            self.max_layer = layer;
+        }
+    }
+
+    fn query(&self, vec: &[f32], k: usize) -> Vec<(String, f32)> {
+        let Some(mut entry) = self.entry_point else {
+            return Vec::new();
+        };
+
+        for l in (1..=self.max_layer).rev() {
+            entry = self.greedy_closest(entry, vec, l);
+        }
+
+        let ef = self.config.ef_search.max(k);
+        let mut found = self.search_layer(entry, vec, 0, ef);
+        found.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        found.truncate(k);
+        found
+            .into_iter()
+            .map(|(idx, d)| (self.nodes[idx].id.clone(), d))
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_index_returns_empty() {
+        let index = HnswIndex::new(HnswConfig::default());
+        assert!(index.query(&[1.0, 0.0], 5).is_empty());
+    }
+
+    #[test]
+    fn finds_exact_match() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        index.insert("a".to_string(), vec![1.0, 0.0, 0.0]);
+        index.insert("b".to_string(), vec![0.0, 1.0, 0.0]);
+        index.insert("c".to_string(), vec![0.0, 0.0, 1.0]);
+
+        let results = index.query(&[1.0, 0.0, 0.0], 1);
+        assert_eq!(results[0].0, "a");
+        assert!(results[0].1 < 1e-4);
+    }
+
+    #[test]
+    fn dimension_mismatch_yields_no_results() {
+        let mut index = HnswIndex::new(HnswConfig::default());
+        index.insert("a".to_string(), vec![1.0, 0.0]);
+
+        assert!(index.query(&[1.0, 0.0, 0.0], 5).is_empty());
+    }
+
+    #[test]
+    fn flat_index_matches_hnsw_ordering() {
+        let mut flat = FlatIndex::new();
+        flat.insert("a".to_string(), vec![1.0, 0.0]);
+        flat.insert("b".to_string(), vec![0.0, 1.0]);
+
+        let results = flat.query(&[0.9, 0.1], 2);
+        assert_eq!(results[0].0, "a");
+
+        let mut hnsw = HnswIndex::new(HnswConfig::default());
+        hnsw.insert("a".to_string(), vec![1.0, 0.0]);
+        hnsw.insert("b".to_string(), vec![0.0, 1.0]);
+
+        let hnsw_ids: Vec<String> = hnsw.query(&[0.9, 0.1], 2).into_iter().map(|(id, _)| id).collect();
+        let flat_ids: Vec<String> = results.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(hnsw_ids, flat_ids);
+    }
+}
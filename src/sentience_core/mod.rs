@@ -1,30 +1,45 @@
+pub mod analysis;
 pub mod ast;
+pub mod belief;
 pub mod canonicalizer;
+pub mod error;
 pub mod executor;
 pub mod hasher;
+pub mod ids;
+pub mod index;
 pub mod parser;
+pub mod query;
 pub mod runtime;
+pub mod store;
 
-use ast::SentienceTokenAst;
+use analysis::analyze;
+use ast::{Program, SentienceToken, SentienceTokenAst};
 use canonicalizer::canonicalize;
-use executor::execute;
+use error::SentienceError;
+use executor::cognitive_cycle;
 use hasher::token_hash;
 use parser::parse_program;
 use runtime::ExecutionResult;
-use runtime::Runtime;
+use runtime::{Runtime, SimpleRuntime};
+use std::io;
 
 /// Main Sentience Core API - matches SRAI specification
 pub struct SentienceCore {
     runtime: Box<dyn Runtime>,
+    /// Tokens the Superego deferred, retried at the start of the next cognitive cycle.
+    pending: Vec<SentienceToken>,
 }
 
 impl SentienceCore {
     pub fn new(runtime: Box<dyn Runtime>) -> Self {
-        Self { runtime }
+        Self {
+            runtime,
+            pending: Vec::new(),
+        }
     }
 
-    /// Parse Sentience DSL into typed AST
-    pub fn parse(&self, src: &str) -> Result<SentienceTokenAst, String> {
+    /// Parse Sentience DSL into a program (every top-level statement as its own AST).
+    pub fn parse(&self, src: &str) -> Result<Program, SentienceError> {
         parse_program(src)
     }
 
@@ -44,32 +59,92 @@ impl SentienceCore {
         symbolic_encoder::encode(canon)
     }
 
-    /// Execute AST against runtime (Cortex + RefNet + Superego)
-    pub fn execute(&mut self, ast: &SentienceTokenAst) -> Result<ExecutionResult, String> {
-        execute(ast, &mut *self.runtime)
+    /// Run one cognitive cycle for every statement in `program` against the runtime (Cortex +
+    /// RefNet + Superego), in order, retrying any previously-deferred tokens first. Per-statement
+    /// results are folded together: `tokens`/`edges`/`blocked`/`consolidated` accumulate across
+    /// statements, while `token_id`/`embedding`/`metrics` reflect the last statement executed.
+    pub fn execute(&mut self, program: &Program) -> Result<ExecutionResult, SentienceError> {
+        let mut result = ExecutionResult::new();
+        for statement in &program.statements {
+            let step = cognitive_cycle(statement, &mut *self.runtime, &mut self.pending)?;
+            result.tokens.extend(step.tokens);
+            result.edges.extend(step.edges);
+            result.blocked.extend(step.blocked);
+            result.consolidated.extend(step.consolidated);
+            result.token_id = step.token_id;
+            result.embedding = step.embedding;
+            result.metrics = step.metrics;
+        }
+        Ok(result)
     }
 
-    /// Complete pipeline: parse → canonicalize → hash → embed → execute
-    pub fn process_step(&mut self, src: &str) -> Result<ExecutionResult, String> {
-        let ast = self.parse(src)?;
-        let canon = self.canonicalize(&ast);
-        let token_id = self.hash(&canon);
-        let embedding = self.embed(&canon);
-
-        // Execute with runtime
-        let mut result = self.execute(&canon)?;
-        result.token_id = Some(token_id);
-        result.embedding = Some(embedding);
+    /// Complete pipeline: parse → canonicalize → analyze → hash → embed → execute, over every
+    /// statement in `src`. Analysis runs after canonicalization and before execution so a program
+    /// with a missing field or a dangling `concept.from` reference is rejected up front, with
+    /// every problem surfaced at once, rather than failing partway through execution.
+    pub fn process_step(&mut self, src: &str) -> Result<ExecutionResult, Vec<SentienceError>> {
+        let program = self.parse(src).map_err(|e| vec![e])?;
+        let canon = Program {
+            statements: program
+                .statements
+                .iter()
+                .map(|ast| self.canonicalize(ast))
+                .collect(),
+        };
+        analyze(&canon, &*self.runtime.cortex())?;
+
+        let last_token_id = canon.statements.last().map(|ast| self.hash(ast));
+        let last_embedding = canon.statements.last().map(|ast| self.embed(ast));
+
+        let mut result = self.execute(&canon).map_err(|e| vec![e])?;
+        result.token_id = last_token_id;
+        result.embedding = last_embedding;
 
         Ok(result)
     }
+
+    /// Persist the Cortex to `path` as a content-addressed snapshot (see [`store`]). Only
+    /// supported when this core's runtime is the in-memory [`SimpleRuntime`]; other backends
+    /// (e.g. a remote/async-backed one) have nothing for `store` to snapshot.
+    pub fn save(&mut self, path: &str) -> io::Result<()> {
+        let runtime = self.simple_runtime_mut()?;
+        store::save(runtime.in_memory_cortex(), path)
+    }
+
+    /// Replace the Cortex with a snapshot loaded from `path` (see [`store`]), discarding any
+    /// tokens the Superego had deferred under the old one. Same backend restriction as [`save`](Self::save).
+    pub fn load(&mut self, path: &str) -> io::Result<()> {
+        let cortex = store::load(path)?;
+        let runtime = self.simple_runtime_mut()?;
+        runtime.restore_cortex(cortex);
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn simple_runtime_mut(&mut self) -> io::Result<&mut SimpleRuntime> {
+        self.runtime
+            .as_any_mut()
+            .downcast_mut::<SimpleRuntime>()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "this runtime backend doesn't support snapshotting",
+                )
+            })
+    }
 }
 
-/// Symbolic encoder for deterministic embeddings
+/// Symbolic encoder for deterministic embeddings.
+///
+/// Part of the `sentience/0.2` embedding spec (see [`hasher::SCHEMA_VERSION`]): every scalar
+/// folded into the embedding comes from SHA-256 over UTF-8 bytes, read back as a big-endian `u64`
+/// from the digest's first 8 bytes. Unlike `std::collections::hash_map::DefaultHasher` (whose
+/// algorithm and seed are explicitly unspecified and can change between Rust releases), this is a
+/// fixed, spec-pinned algorithm, so two nodes on different toolchains or architectures that commit
+/// the same AST always derive the same embedding.
 mod symbolic_encoder {
     use super::ast::*;
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
+    use sha2::{Digest, Sha256};
 
     pub fn encode(ast: &SentienceTokenAst) -> Vec<f32> {
         let mut embedding = vec![0.0; 256];
@@ -96,10 +171,11 @@ mod symbolic_encoder {
         embedding
     }
 
+    /// Fixed, spec-pinned 64-bit hash: the first 8 bytes of `SHA-256(s)`, read back big-endian so
+    /// the result doesn't depend on the host's native byte order.
     fn hash_string(s: &str) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        s.hash(&mut hasher);
-        hasher.finish()
+        let digest = Sha256::digest(s.as_bytes());
+        u64::from_be_bytes(digest[..8].try_into().expect("digest is at least 8 bytes"))
     }
 
     fn distribute_hash(hash: u64, embedding: &mut [f32], offset: usize) {
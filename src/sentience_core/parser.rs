@@ -1,58 +1,486 @@
+//! Lexer + recursive-descent parser for the Sentience Core DSL.
+//!
+//! Replaces the original line-by-line `starts_with` scanner: statements are tokenized with real
+//! positions, `reflect { ... }` bodies parse into an arbitrary op list instead of a fixed triple,
+//! and `concept`/`action`/`self_model` blocks support field maps and lists.
+
 use crate::sentience_core::ast::*;
+use crate::sentience_core::error::SentienceError;
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Agent,
+    Embed,
+    Reflect,
+    Concept,
+    Action,
+    SelfModel,
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Arrow,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Colon,
+    Comma,
+    Semicolon,
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    span: Span,
+}
 
-/// Parse Sentience DSL into typed AST
-pub fn parse_program(src: &str) -> Result<SentienceTokenAst, String> {
-    let lines: Vec<&str> = src.lines().collect();
-    let mut tokens = Vec::new();
+struct Lexer<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    col: usize,
+    _input: &'a str,
+}
 
-    for (line_num, line) in lines.iter().enumerate() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("//") {
-            continue;
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Lexer {
+            chars: input.chars().collect(),
+            pos: 0,
+            line: 1,
+            col: 1,
+            _input: input,
         }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+        Some(c)
+    }
 
-        if trimmed.starts_with("agent ") {
-            // Parse agent declaration
-            let agent_name = trimmed.strip_prefix("agent ").unwrap_or("unknown");
-            let span = Span::new(line_num + 1, 1, line_num + 1, trimmed.len());
-            let ast = SentienceTokenAst::new(ThoughtType::SelfModel, span)
-                .with_field("name".to_string(), Value::Str(agent_name.to_string()));
-            tokens.push(ast);
-        } else if trimmed.starts_with("embed ") {
-            // Parse embed statement
-            if let Some(embed_content) = trimmed.strip_prefix("embed ") {
-                let parts: Vec<&str> = embed_content.split(" -> ").collect();
-                if parts.len() == 2 {
-                    let span = Span::new(line_num + 1, 1, line_num + 1, trimmed.len());
-                    let ast = SentienceTokenAst::new(ThoughtType::Percept, span)
-                        .with_field("modality".to_string(), Value::Str("text".to_string()))
-                        .with_field("content".to_string(), Value::Str(parts[0].to_string()))
-                        .with_field("target".to_string(), Value::Str(parts[1].to_string()));
-                    tokens.push(ast);
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+                self.advance();
+            }
+            if self.peek() == Some('/') && self.peek_at(1) == Some('/') {
+                while self.peek().is_some() && self.peek() != Some('\n') {
+                    self.advance();
                 }
+                continue;
             }
-        } else if trimmed.starts_with("reflect {") {
-            // Parse reflection block
-            let span = Span::new(line_num + 1, 1, line_num + 1, trimmed.len());
-            let ast = SentienceTokenAst::new(ThoughtType::Reflection, span).with_field(
-                "ops".to_string(),
-                Value::List(vec![
-                    Value::Str("recall".to_string()),
-                    Value::Str("reframe".to_string()),
-                    Value::Str("consolidate".to_string()),
-                ]),
-            );
-            tokens.push(ast);
+            break;
         }
     }
 
-    if tokens.is_empty() {
-        return Err("No valid tokens found in input".to_string());
+    fn tokenize(mut self) -> Result<Vec<Token>, SentienceError> {
+        let mut tokens = Vec::new();
+        loop {
+            self.skip_whitespace_and_comments();
+            let (line, col) = (self.line, self.col);
+
+            let Some(c) = self.peek() else {
+                tokens.push(Token {
+                    kind: TokenKind::Eof,
+                    span: Span::single_char(line, col),
+                });
+                break;
+            };
+
+            let kind = match c {
+                '{' => {
+                    self.advance();
+                    TokenKind::LBrace
+                }
+                '}' => {
+                    self.advance();
+                    TokenKind::RBrace
+                }
+                '[' => {
+                    self.advance();
+                    TokenKind::LBracket
+                }
+                ']' => {
+                    self.advance();
+                    TokenKind::RBracket
+                }
+                ':' => {
+                    self.advance();
+                    TokenKind::Colon
+                }
+                ',' => {
+                    self.advance();
+                    TokenKind::Comma
+                }
+                ';' => {
+                    self.advance();
+                    TokenKind::Semicolon
+                }
+                '-' if self.peek_at(1) == Some('>') => {
+                    self.advance();
+                    self.advance();
+                    TokenKind::Arrow
+                }
+                '"' => {
+                    self.advance();
+                    let mut s = String::new();
+                    while let Some(ch) = self.peek() {
+                        if ch == '"' {
+                            break;
+                        }
+                        s.push(ch);
+                        self.advance();
+                    }
+                    if self.peek() != Some('"') {
+                        return Err(SentienceError::new(
+                            "E_UNTERMINATED_STRING",
+                            "unterminated string literal",
+                            Span::single_char(line, col),
+                        ));
+                    }
+                    self.advance();
+                    TokenKind::Str(s)
+                }
+                c if c.is_ascii_digit()
+                    || (c == '-' && self.peek_at(1).map_or(false, |n| n.is_ascii_digit())) =>
+                {
+                    let mut s = String::new();
+                    if c == '-' {
+                        s.push(c);
+                        self.advance();
+                    }
+                    while matches!(self.peek(), Some(d) if d.is_ascii_digit()) {
+                        s.push(self.advance().unwrap());
+                    }
+                    if self.peek() == Some('.')
+                        && self.peek_at(1).map_or(false, |d| d.is_ascii_digit())
+                    {
+                        s.push(self.advance().unwrap());
+                        while matches!(self.peek(), Some(d) if d.is_ascii_digit()) {
+                            s.push(self.advance().unwrap());
+                        }
+                    }
+                    let n: f64 = s.parse().map_err(|_| {
+                        SentienceError::new(
+                            "E_INVALID_NUMBER",
+                            format!("invalid number '{}'", s),
+                            Span::single_char(line, col),
+                        )
+                    })?;
+                    TokenKind::Num(n)
+                }
+                c if is_ident_start(c) => {
+                    let mut s = String::new();
+                    while matches!(self.peek(), Some(d) if is_ident_continue(d)) {
+                        s.push(self.advance().unwrap());
+                    }
+                    match s.as_str() {
+                        "agent" => TokenKind::Agent,
+                        "embed" => TokenKind::Embed,
+                        "reflect" => TokenKind::Reflect,
+                        "concept" => TokenKind::Concept,
+                        "action" => TokenKind::Action,
+                        "self_model" => TokenKind::SelfModel,
+                        "true" => TokenKind::Bool(true),
+                        "false" => TokenKind::Bool(false),
+                        _ => TokenKind::Ident(s),
+                    }
+                }
+                other => {
+                    return Err(SentienceError::new(
+                        "E_UNEXPECTED_CHAR",
+                        format!("unexpected character '{}'", other),
+                        Span::single_char(line, col),
+                    ));
+                }
+            };
+
+            tokens.push(Token {
+                kind,
+                span: Span::new(line, col, self.line, self.col),
+            });
+        }
+        Ok(tokens)
     }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
 
-    // Return the first token for now
-    // In a full implementation, you'd return a proper program structure
-    Ok(tokens[0].clone())
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Parser { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> &TokenKind {
+        &self.tokens[self.pos].kind
+    }
+
+    fn peek_span(&self) -> Span {
+        self.tokens[self.pos].span.clone()
+    }
+
+    fn advance(&mut self) -> Token {
+        let tok = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn expect(&mut self, kind: &TokenKind, what: &str) -> Result<Token, SentienceError> {
+        if std::mem::discriminant(self.peek()) == std::mem::discriminant(kind) {
+            Ok(self.advance())
+        } else {
+            Err(SentienceError::new(
+                "E_UNEXPECTED_TOKEN",
+                format!("expected {}, found {:?}", what, self.peek()),
+                self.peek_span(),
+            ))
+        }
+    }
+
+    fn parse_program(&mut self) -> Result<Program, SentienceError> {
+        let mut statements = Vec::new();
+        while !matches!(self.peek(), TokenKind::Eof) {
+            statements.push(self.parse_statement()?);
+        }
+        Ok(Program { statements })
+    }
+
+    fn parse_statement(&mut self) -> Result<SentienceTokenAst, SentienceError> {
+        match self.peek().clone() {
+            TokenKind::Agent => self.parse_agent(),
+            TokenKind::Embed => self.parse_embed(),
+            TokenKind::Reflect => self.parse_reflect(),
+            TokenKind::Concept => self.parse_block(ThoughtType::Concept, TokenKind::Concept),
+            TokenKind::Action => self.parse_block(ThoughtType::Action, TokenKind::Action),
+            TokenKind::SelfModel => self.parse_block(ThoughtType::SelfModel, TokenKind::SelfModel),
+            other => Err(SentienceError::new(
+                "E_UNEXPECTED_TOKEN",
+                format!("unexpected token {:?}", other),
+                self.peek_span(),
+            )),
+        }
+    }
+
+    fn parse_agent(&mut self) -> Result<SentienceTokenAst, SentienceError> {
+        let start = self.advance().span; // 'agent'
+        let name_tok = self.advance();
+        let name = match name_tok.kind {
+            TokenKind::Ident(s) => s,
+            other => {
+                return Err(SentienceError::new(
+                    "E_UNEXPECTED_TOKEN",
+                    format!("expected agent name, found {:?}", other),
+                    name_tok.span,
+                ))
+            }
+        };
+        let span = Span::new(
+            start.line,
+            start.col,
+            name_tok.span.end_line,
+            name_tok.span.end_col,
+        );
+        Ok(SentienceTokenAst::new(ThoughtType::SelfModel, span)
+            .with_field("name".to_string(), Value::Str(name)))
+    }
+
+    fn parse_embed(&mut self) -> Result<SentienceTokenAst, SentienceError> {
+        let start = self.advance().span; // 'embed'
+        let content = self.parse_ident_or_string("embed content")?;
+        self.expect(&TokenKind::Arrow, "'->'")?;
+        let target_tok = self.advance();
+        let target = match target_tok.kind {
+            TokenKind::Ident(s) => s,
+            TokenKind::Str(s) => s,
+            other => {
+                return Err(SentienceError::new(
+                    "E_UNEXPECTED_TOKEN",
+                    format!("expected embed target, found {:?}", other),
+                    target_tok.span,
+                ))
+            }
+        };
+        let span = Span::new(
+            start.line,
+            start.col,
+            target_tok.span.end_line,
+            target_tok.span.end_col,
+        );
+        Ok(SentienceTokenAst::new(ThoughtType::Percept, span)
+            .with_field("modality".to_string(), Value::Str("text".to_string()))
+            .with_field("content".to_string(), Value::Str(content))
+            .with_field("target".to_string(), Value::Str(target)))
+    }
+
+    fn parse_ident_or_string(&mut self, what: &str) -> Result<String, SentienceError> {
+        let tok = self.advance();
+        match tok.kind {
+            TokenKind::Ident(s) => Ok(s),
+            TokenKind::Str(s) => Ok(s),
+            other => Err(SentienceError::new(
+                "E_UNEXPECTED_TOKEN",
+                format!("expected {}, found {:?}", what, other),
+                tok.span,
+            )),
+        }
+    }
+
+    fn parse_reflect(&mut self) -> Result<SentienceTokenAst, SentienceError> {
+        let start = self.advance().span; // 'reflect'
+        self.expect(&TokenKind::LBrace, "'{'")?;
+        let mut ops = Vec::new();
+        while !matches!(self.peek(), TokenKind::RBrace) {
+            let tok = self.advance();
+            match tok.kind {
+                TokenKind::Ident(s) => ops.push(Value::Str(s)),
+                TokenKind::Semicolon | TokenKind::Comma => {}
+                other => {
+                    return Err(SentienceError::new(
+                        "E_UNEXPECTED_TOKEN",
+                        format!("expected an op name, found {:?}", other),
+                        tok.span,
+                    ))
+                }
+            }
+        }
+        let end = self.expect(&TokenKind::RBrace, "'}'")?.span;
+        let span = Span::new(start.line, start.col, end.end_line, end.end_col);
+        Ok(SentienceTokenAst::new(ThoughtType::Reflection, span)
+            .with_field("ops".to_string(), Value::List(ops)))
+    }
+
+    fn parse_block(
+        &mut self,
+        ttype: ThoughtType,
+        expected: TokenKind,
+    ) -> Result<SentienceTokenAst, SentienceError> {
+        let start = self.expect(&expected, "block keyword")?.span;
+        self.expect(&TokenKind::LBrace, "'{'")?;
+        let fields = self
+            .parse_field_list()
+            .map_err(|e| e.with_label(start.clone(), "block opened here"))?;
+        let end = self
+            .expect(&TokenKind::RBrace, "'}'")
+            .map_err(|e| e.with_label(start.clone(), "block opened here"))?
+            .span;
+        let span = Span::new(start.line, start.col, end.end_line, end.end_col);
+
+        let mut ast = SentienceTokenAst::new(ttype, span);
+        for (key, value) in fields {
+            ast = ast.with_field(key, value);
+        }
+        Ok(ast)
+    }
+
+    /// `key: value, key: value, ...` up to (not including) the closing `}`.
+    fn parse_field_list(&mut self) -> Result<Vec<(String, Value)>, SentienceError> {
+        let mut fields = Vec::new();
+        while !matches!(self.peek(), TokenKind::RBrace) {
+            let key_tok = self.advance();
+            let key = match key_tok.kind {
+                TokenKind::Ident(s) => s,
+                other => {
+                    return Err(SentienceError::new(
+                        "E_UNEXPECTED_TOKEN",
+                        format!("expected a field name, found {:?}", other),
+                        key_tok.span,
+                    ))
+                }
+            };
+            self.expect(&TokenKind::Colon, "':'")?;
+            let value = self.parse_value()?;
+            fields.push((key, value));
+            if matches!(self.peek(), TokenKind::Comma) {
+                self.advance();
+            }
+        }
+        Ok(fields)
+    }
+
+    fn parse_value(&mut self) -> Result<Value, SentienceError> {
+        match self.peek().clone() {
+            TokenKind::Str(s) => {
+                self.advance();
+                Ok(Value::Str(s))
+            }
+            TokenKind::Num(n) => {
+                self.advance();
+                Ok(Value::Num(n))
+            }
+            TokenKind::Bool(b) => {
+                self.advance();
+                Ok(Value::Bool(b))
+            }
+            TokenKind::Ident(s) => {
+                self.advance();
+                if s.contains('.') {
+                    Ok(Value::Path(s.split('.').map(|p| p.to_string()).collect()))
+                } else {
+                    Ok(Value::Str(s))
+                }
+            }
+            TokenKind::LBracket => {
+                self.advance();
+                let mut items = Vec::new();
+                while !matches!(self.peek(), TokenKind::RBracket) {
+                    items.push(self.parse_value()?);
+                    if matches!(self.peek(), TokenKind::Comma) {
+                        self.advance();
+                    }
+                }
+                self.expect(&TokenKind::RBracket, "']'")?;
+                Ok(Value::List(items))
+            }
+            TokenKind::LBrace => {
+                self.advance();
+                let fields = self.parse_field_list()?;
+                self.expect(&TokenKind::RBrace, "'}'")?;
+                Ok(Value::Map(fields))
+            }
+            other => Err(SentienceError::new(
+                "E_UNEXPECTED_TOKEN",
+                format!("expected a value, found {:?}", other),
+                self.peek_span(),
+            )),
+        }
+    }
+}
+
+/// Parse Sentience DSL into a full program: every top-level statement becomes its own
+/// [`SentienceTokenAst`].
+pub fn parse_program(src: &str) -> Result<Program, SentienceError> {
+    let tokens = Lexer::new(src).tokenize()?;
+    Parser::new(tokens).parse_program()
 }
 
 #[cfg(test)]
@@ -60,40 +488,89 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_agent() {
-        let src = "agent TestAgent";
-        let result = parse_program(src);
-        assert!(result.is_ok());
+    fn parses_an_agent_declaration() {
+        let program = parse_program("agent TestAgent").unwrap();
+        assert_eq!(program.statements.len(), 1);
 
-        let ast = result.unwrap();
+        let ast = &program.statements[0];
         assert_eq!(ast.ttype, ThoughtType::SelfModel);
         assert_eq!(ast.get_field_str("name"), Some("TestAgent"));
     }
 
     #[test]
-    fn test_parse_embed() {
-        let src = "embed msg -> percept.text";
-        let result = parse_program(src);
-        assert!(result.is_ok());
-
-        let ast = result.unwrap();
+    fn parses_an_embed_statement() {
+        let program = parse_program("embed msg -> percept.text").unwrap();
+        let ast = &program.statements[0];
         assert_eq!(ast.ttype, ThoughtType::Percept);
         assert_eq!(ast.get_field_str("content"), Some("msg"));
         assert_eq!(ast.get_field_str("target"), Some("percept.text"));
     }
 
     #[test]
-    fn test_parse_reflect() {
-        let src = "reflect { recall; reframe; consolidate }";
-        let result = parse_program(src);
-        assert!(result.is_ok());
-
-        let ast = result.unwrap();
+    fn parses_a_reflect_block_with_an_arbitrary_op_list() {
+        let program =
+            parse_program("reflect { recall; reframe; consolidate; prioritize }").unwrap();
+        let ast = &program.statements[0];
         assert_eq!(ast.ttype, ThoughtType::Reflection);
-        if let Some(Value::List(ops)) = ast.get_field("ops") {
-            assert_eq!(ops.len(), 3);
-        } else {
-            panic!("Expected ops field to be a list");
+        match ast.get_field("ops") {
+            Some(Value::List(ops)) => assert_eq!(ops.len(), 4),
+            other => panic!("expected ops field to be a list, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_a_concept_block_with_fields_and_a_list() {
+        let src = r#"concept { summary: "shared theme", strength: 0.8, from: ["t1", "t2"] }"#;
+        let program = parse_program(src).unwrap();
+        let ast = &program.statements[0];
+
+        assert_eq!(ast.ttype, ThoughtType::Concept);
+        assert_eq!(ast.get_field_str("summary"), Some("shared theme"));
+        assert_eq!(ast.get_field_num("strength"), Some(0.8));
+        match ast.get_field("from") {
+            Some(Value::List(items)) => assert_eq!(items.len(), 2),
+            other => panic!("expected from field to be a list, got {:?}", other),
         }
     }
+
+    #[test]
+    fn parses_a_multi_statement_program() {
+        let src = "agent TestAgent\nembed msg -> percept.text\nreflect { recall }";
+        let program = parse_program(src).unwrap();
+        assert_eq!(program.statements.len(), 3);
+        assert_eq!(program.statements[0].ttype, ThoughtType::SelfModel);
+        assert_eq!(program.statements[1].ttype, ThoughtType::Percept);
+        assert_eq!(program.statements[2].ttype, ThoughtType::Reflection);
+    }
+
+    #[test]
+    fn parses_action_and_self_model_blocks() {
+        let src = r#"action { name: "consolidate", target: "goal_1" }
+self_model { name: "core", confidence: 0.9 }"#;
+        let program = parse_program(src).unwrap();
+
+        assert_eq!(program.statements[0].ttype, ThoughtType::Action);
+        assert_eq!(
+            program.statements[0].get_field_str("name"),
+            Some("consolidate")
+        );
+
+        assert_eq!(program.statements[1].ttype, ThoughtType::SelfModel);
+        assert_eq!(program.statements[1].get_field_num("confidence"), Some(0.9));
+    }
+
+    #[test]
+    fn reports_a_span_accurate_error_for_an_unterminated_string() {
+        let err = parse_program("embed \"unterminated -> percept.text").unwrap_err();
+        assert_eq!(err.code, "E_UNTERMINATED_STRING");
+        assert_eq!(err.span.line, 1);
+    }
+
+    #[test]
+    fn labels_an_unclosed_block_with_where_it_was_opened() {
+        let err = parse_program("concept { summary: \"x\"").unwrap_err();
+        assert_eq!(err.code, "E_UNEXPECTED_TOKEN");
+        assert_eq!(err.labels.len(), 1, "expected a 'block opened here' label");
+        assert_eq!(err.labels[0].0.col, 1);
+    }
 }
@@ -0,0 +1,266 @@
+//! Dataspace-style pattern queries over the token/edge graph.
+//!
+//! A [`Pattern`] describes a token shape to search for: a [`ThoughtType`] constraint, field
+//! constraints (`key == literal`, or `key` bound to a capture variable), and outgoing-edge
+//! constraints of the form `--EdgeType--> <sub-pattern>`. [`match_token`] walks the graph and
+//! fills a [`Bindings`] map with capture variable -> matched value/id, mirroring dataspace
+//! assertion patterns with variable binders. This is meant to back a future `query { ... }`
+//! statement in the parser.
+
+use crate::sentience_core::ast::{EdgeType, SentienceToken, ThoughtType, Value};
+use crate::sentience_core::runtime::{Cortex, InMemoryCortex};
+use std::collections::{HashMap, HashSet};
+
+/// A leaf constraint on a single field's value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatternValue {
+    /// Matches only this exact value.
+    Lit(Value),
+    /// Matches any value and binds it to `name`.
+    Bind(String),
+    /// Matches any value without binding it.
+    Discard,
+}
+
+/// A required outgoing edge: `--edge_type--> <to>`, where `to` is the sub-pattern the edge's
+/// target token must match.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdgePattern {
+    pub edge_type: EdgeType,
+    pub to: Box<Pattern>,
+}
+
+/// A token shape to search the graph for.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Pattern {
+    pub ttype: Option<ThoughtType>,
+    pub fields: Vec<(String, PatternValue)>,
+    pub edges: Vec<EdgePattern>,
+    /// Bind the matched token's own id to this name.
+    pub bind_id: Option<String>,
+}
+
+impl Pattern {
+    pub fn new(ttype: Option<ThoughtType>) -> Self {
+        Pattern {
+            ttype,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_field(mut self, key: impl Into<String>, value: PatternValue) -> Self {
+        self.fields.push((key.into(), value));
+        self
+    }
+
+    pub fn with_edge(mut self, edge_type: EdgeType, to: Pattern) -> Self {
+        self.edges.push(EdgePattern {
+            edge_type,
+            to: Box::new(to),
+        });
+        self
+    }
+
+    pub fn bind_id(mut self, name: impl Into<String>) -> Self {
+        self.bind_id = Some(name.into());
+        self
+    }
+}
+
+/// Variable bindings produced by a successful match: capture name -> matched field value or
+/// token id.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bindings {
+    pub values: HashMap<String, Value>,
+    pub ids: HashMap<String, String>,
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Match `pattern` against `token` within `cortex`, recording captures into `bindings`.
+pub fn match_token(
+    pattern: &Pattern,
+    token: &SentienceToken,
+    cortex: &InMemoryCortex,
+    bindings: &mut Bindings,
+) -> bool {
+    let mut visited = HashSet::new();
+    match_token_inner(pattern, token, cortex, bindings, &mut visited)
+}
+
+/// Find every token in `cortex` matching `pattern`, returning the bindings produced by each
+/// match (one set of bindings per matching token).
+pub fn query(cortex: &InMemoryCortex, pattern: &Pattern) -> Vec<Bindings> {
+    cortex
+        .all_tokens()
+        .values()
+        .filter_map(|token| {
+            let mut bindings = Bindings::new();
+            match_token(pattern, token, cortex, &mut bindings).then_some(bindings)
+        })
+        .collect()
+}
+
+/// `visited` guards against cycles in the edge graph: each `(pattern, token)` pair is tried at
+/// most once per [`match_token`] call (pairs are never removed from the set), so a pattern
+/// chasing edges around a cyclic graph still terminates. A token reached a second time under the
+/// same sub-pattern, even along a different path, does not match on that second visit.
+fn match_token_inner(
+    pattern: &Pattern,
+    token: &SentienceToken,
+    cortex: &InMemoryCortex,
+    bindings: &mut Bindings,
+    visited: &mut HashSet<(*const Pattern, String)>,
+) -> bool {
+    if !visited.insert((pattern as *const Pattern, token.id.clone())) {
+        return false;
+    }
+
+    if let Some(want) = &pattern.ttype {
+        if &token.ast.ttype != want {
+            return false;
+        }
+    }
+
+    for (key, constraint) in &pattern.fields {
+        let actual = match token.ast.get_field(key) {
+            Some(v) => v,
+            None => return false,
+        };
+        if !match_value(constraint, actual, bindings) {
+            return false;
+        }
+    }
+
+    for edge_pattern in &pattern.edges {
+        let matched = cortex.all_edges().values().any(|edge| {
+            edge.source_id == token.id
+                && edge.edge_type == edge_pattern.edge_type
+                && cortex
+                    .get_token(&edge.target_id)
+                    .map(|target| {
+                        match_token_inner(&edge_pattern.to, &target, cortex, bindings, visited)
+                    })
+                    .unwrap_or(false)
+        });
+        if !matched {
+            return false;
+        }
+    }
+
+    if let Some(name) = &pattern.bind_id {
+        bindings.ids.insert(name.clone(), token.id.clone());
+    }
+
+    true
+}
+
+fn match_value(constraint: &PatternValue, actual: &Value, bindings: &mut Bindings) -> bool {
+    match constraint {
+        PatternValue::Discard => true,
+        PatternValue::Lit(want) => want == actual,
+        PatternValue::Bind(name) => {
+            bindings.values.insert(name.clone(), actual.clone());
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentience_core::ast::{Edge, Provenance, Span, SentienceTokenAst, TokenMeta};
+
+    fn token(id: &str, ttype: ThoughtType, fields: Vec<(&str, Value)>) -> SentienceToken {
+        let mut ast = SentienceTokenAst::new(ttype, Span::new(1, 1, 1, 1));
+        for (key, value) in fields {
+            ast = ast.with_field(key.to_string(), value);
+        }
+        SentienceToken::new(
+            id.to_string(),
+            ast,
+            Vec::new(),
+            Provenance {
+                stm_ids: Vec::new(),
+                refnet_id: "test".to_string(),
+                rules_applied: Vec::new(),
+                agent_id: "test".to_string(),
+                step_id: 0,
+                timestamp: 0,
+            },
+            TokenMeta {
+                version: "test".to_string(),
+                strength: 1.0,
+                belief: 1.0,
+                tags: Vec::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn matches_field_literal_and_binds_capture() {
+        let mut cortex = InMemoryCortex::new(8);
+        let goal = token(
+            "goal_1",
+            ThoughtType::Goal,
+            vec![("text", Value::Str("ship it".to_string()))],
+        );
+        Cortex::commit(&mut cortex, &goal, &[]).unwrap();
+
+        let pattern = Pattern::new(Some(ThoughtType::Goal))
+            .with_field("text", PatternValue::Lit(Value::Str("ship it".to_string())))
+            .with_field("text", PatternValue::Bind("text".to_string()));
+        let mut bindings = Bindings::new();
+
+        assert!(match_token(&pattern, &goal, &cortex, &mut bindings));
+        assert_eq!(
+            bindings.values.get("text"),
+            Some(&Value::Str("ship it".to_string()))
+        );
+    }
+
+    #[test]
+    fn finds_reflections_supporting_a_goal_above_strength() {
+        let mut cortex = InMemoryCortex::new(8);
+
+        let goal = token(
+            "goal_1",
+            ThoughtType::Goal,
+            vec![
+                ("text", Value::Str("ship it".to_string())),
+                ("strength", Value::Num(0.9)),
+            ],
+        );
+        // `commit` assigns each token its real content-hash id rather than keeping whatever
+        // placeholder it arrived with, so the edge below must reference the hashes `commit` will
+        // actually use, not the placeholders `token()` stamped on for convenience.
+        let goal_id = Cortex::commit(&mut cortex, &goal, &[]).unwrap();
+
+        let reflection = token("reflection_1", ThoughtType::Reflection, vec![]);
+        let reflection_id = crate::sentience_core::hasher::token_hash(&reflection.ast);
+        let edge = Edge::new(reflection_id, goal_id, EdgeType::Supports, 1.0, 0);
+        Cortex::commit(&mut cortex, &reflection, &[edge]).unwrap();
+
+        let pattern = Pattern::new(Some(ThoughtType::Reflection)).with_edge(
+            EdgeType::Supports,
+            Pattern::new(Some(ThoughtType::Goal))
+                .with_field("text", PatternValue::Bind("goal_text".to_string()))
+                .with_field("strength", PatternValue::Bind("strength".to_string())),
+        );
+
+        let above_threshold: Vec<Bindings> = query(&cortex, &pattern)
+            .into_iter()
+            .filter(|b| matches!(b.values.get("strength"), Some(Value::Num(n)) if *n > 0.5))
+            .collect();
+
+        assert_eq!(above_threshold.len(), 1);
+        assert_eq!(
+            above_threshold[0].values.get("goal_text"),
+            Some(&Value::Str("ship it".to_string()))
+        );
+    }
+}
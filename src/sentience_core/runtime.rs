@@ -1,4 +1,6 @@
 use crate::sentience_core::ast::*;
+use crate::sentience_core::hasher::token_hash;
+use crate::sentience_core::index::{HnswConfig, HnswIndex, VectorIndex};
 use std::collections::HashMap;
 
 /// Execution result from Sentience Core
@@ -9,6 +11,10 @@ pub struct ExecutionResult {
     pub token_id: Option<String>,
     pub embedding: Option<Vec<f32>>,
     pub metrics: Option<RefMetrics>,
+    /// Reasons the Superego blocked a token outright this cycle (as opposed to deferring it).
+    pub blocked: Vec<String>,
+    /// Recall results pulled when RefNet's metrics pointed at a consolidation step.
+    pub consolidated: Vec<TokenRef>,
 }
 
 impl ExecutionResult {
@@ -19,6 +25,8 @@ impl ExecutionResult {
             token_id: None,
             embedding: None,
             metrics: None,
+            blocked: Vec::new(),
+            consolidated: Vec::new(),
         }
     }
 }
@@ -47,6 +55,11 @@ pub trait Runtime: Send {
     fn cortex(&mut self) -> &mut dyn Cortex;
     fn refnet(&self) -> &dyn RefNet;
     fn superego(&self) -> &dyn Superego;
+
+    /// Downcast hook for backends that support a capability beyond the `Runtime` interface
+    /// itself, e.g. [`store`](crate::sentience_core::store)'s content-addressed snapshotting,
+    /// which only [`InMemoryCortex`] can do.
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
 }
 
 /// Cortex interface for memory operations
@@ -85,29 +98,78 @@ impl TokenRef {
     }
 }
 
-/// In-memory Cortex implementation for testing
+/// In-memory Cortex implementation for testing, backed by a pluggable [`VectorIndex`] for
+/// `recall_similar` (HNSW by default; swap in a `FlatIndex` for small stores where the brute-force
+/// scan is cheaper than maintaining a graph).
 pub struct InMemoryCortex {
     tokens: HashMap<String, SentienceToken>,
     edges: HashMap<String, Edge>,
     stm_window: Vec<String>,
     max_stm_size: usize,
+    index: Box<dyn VectorIndex>,
 }
 
 impl InMemoryCortex {
     pub fn new(max_stm_size: usize) -> Self {
+        Self::with_index(max_stm_size, Box::new(HnswIndex::new(HnswConfig::default())))
+    }
+
+    pub fn with_index(max_stm_size: usize, index: Box<dyn VectorIndex>) -> Self {
         Self {
             tokens: HashMap::new(),
             edges: HashMap::new(),
             stm_window: Vec::new(),
             max_stm_size,
+            index,
         }
     }
+
+    /// Rebuild a Cortex from previously-committed state, e.g. when restoring a snapshot.
+    /// Re-populates the vector index from each token's embedding.
+    pub fn restore(
+        max_stm_size: usize,
+        tokens: HashMap<String, SentienceToken>,
+        edges: HashMap<String, Edge>,
+        stm_window: Vec<String>,
+    ) -> Self {
+        let mut cortex = Self::new(max_stm_size);
+        for token in tokens.values() {
+            cortex.index.insert(token.id.clone(), token.embedding.clone());
+        }
+        cortex.tokens = tokens;
+        cortex.edges = edges;
+        cortex.stm_window = stm_window;
+        cortex
+    }
+
+    pub fn all_tokens(&self) -> &HashMap<String, SentienceToken> {
+        &self.tokens
+    }
+
+    pub fn all_edges(&self) -> &HashMap<String, Edge> {
+        &self.edges
+    }
+
+    pub fn stm_window(&self) -> &[String] {
+        &self.stm_window
+    }
+
+    pub fn max_stm_size(&self) -> usize {
+        self.max_stm_size
+    }
 }
 
 impl Cortex for InMemoryCortex {
     fn commit(&mut self, token: &SentienceToken, edges: &[Edge]) -> Result<String, String> {
-        // Store token
-        self.tokens.insert(token.id.clone(), token.clone());
+        // Every token arrives with the placeholder id its `create_*_token` helper stamped on it
+        // (see executor.rs); replace it with its real content hash here so same-type tokens don't
+        // collide on that placeholder in `tokens`/`index`/`stm_window`.
+        let id = token_hash(&token.ast);
+        let mut token = token.clone();
+        token.id = id.clone();
+
+        self.tokens.insert(id.clone(), token.clone());
+        self.index.insert(id.clone(), token.embedding.clone());
 
         // Store edges
         for edge in edges {
@@ -115,33 +177,20 @@ impl Cortex for InMemoryCortex {
         }
 
         // Update STM window
-        self.stm_window.push(token.id.clone());
+        self.stm_window.push(id.clone());
         if self.stm_window.len() > self.max_stm_size {
             self.stm_window.remove(0);
         }
 
-        Ok(token.id.clone())
+        Ok(id)
     }
 
     fn recall_similar(&self, vec: &[f32], k: usize) -> Vec<TokenRef> {
-        // Simple cosine similarity for now
-        let mut similarities: Vec<(String, f32)> = Vec::new();
-
-        for (id, token) in &self.tokens {
-            let similarity = cosine_similarity(vec, &token.embedding);
-            similarities.push((id.clone(), similarity));
-        }
-
-        // Sort by similarity and take top k
-        similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-        similarities
+        self.index
+            .query(vec, k)
             .into_iter()
-            .take(k)
-            .map(|(id, _)| {
-                let token = &self.tokens[&id];
-                TokenRef::new(id, token.ast.ttype.clone(), token.embedding.clone())
-            })
+            .filter_map(|(id, _)| self.tokens.get(&id))
+            .map(|token| TokenRef::new(token.id.clone(), token.ast.ttype.clone(), token.embedding.clone()))
             .collect()
     }
 
@@ -214,6 +263,16 @@ impl SimpleRuntime {
             superego: StubSuperego,
         }
     }
+
+    /// The concrete in-memory Cortex, for [`store`](crate::sentience_core::store) to snapshot.
+    pub fn in_memory_cortex(&self) -> &InMemoryCortex {
+        &self.cortex
+    }
+
+    /// Replace the Cortex wholesale, e.g. after [`store::load`](crate::sentience_core::store::load).
+    pub fn restore_cortex(&mut self, cortex: InMemoryCortex) {
+        self.cortex = cortex;
+    }
 }
 
 impl Runtime for SimpleRuntime {
@@ -228,20 +287,89 @@ impl Runtime for SimpleRuntime {
     fn superego(&self) -> &dyn Superego {
         &self.superego
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+/// Async counterpart to [`Cortex`] for backends whose memory operations are latency-bound
+/// (a remote vector DB, a disk-backed store, ...). Mirrors the sync interface method-for-method
+/// so the executor can be written once against either.
+#[async_trait::async_trait]
+pub trait AsyncCortex: Send + Sync {
+    async fn commit(&mut self, token: &SentienceToken, edges: &[Edge]) -> Result<String, String>;
+    async fn recall_similar(&self, vec: &[f32], k: usize) -> Vec<TokenRef>;
+    async fn stm(&self, n: usize) -> Vec<TokenRef>;
+    async fn get_token(&self, id: &str) -> Option<SentienceToken>;
+}
+
+/// Async counterpart to [`Runtime`]. Like `Runtime`, `refnet`/`superego` stay synchronous since
+/// evaluation and gating are pure functions of already-fetched data; only the Cortex handle needs
+/// to cross a network or disk boundary.
+pub trait AsyncRuntime: Send {
+    fn cortex(&mut self) -> &mut dyn AsyncCortex;
+    fn refnet(&self) -> &dyn RefNet;
+    fn superego(&self) -> &dyn Superego;
+}
+
+/// Adapts an [`AsyncCortex`] to the synchronous [`Cortex`] interface by blocking on each call,
+/// the way a sync client blocks on the response of what the async client would merely fire and
+/// forget. Lets code written against `SimpleRuntime` keep working unmodified against a
+/// latency-bound backend.
+pub struct BlockingCortex<C: AsyncCortex> {
+    inner: C,
+}
+
+impl<C: AsyncCortex> BlockingCortex<C> {
+    pub fn new(inner: C) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: AsyncCortex> Cortex for BlockingCortex<C> {
+    fn commit(&mut self, token: &SentienceToken, edges: &[Edge]) -> Result<String, String> {
+        futures::executor::block_on(self.inner.commit(token, edges))
+    }
+
+    fn recall_similar(&self, vec: &[f32], k: usize) -> Vec<TokenRef> {
+        futures::executor::block_on(self.inner.recall_similar(vec, k))
+    }
+
+    fn stm(&self, n: usize) -> Vec<TokenRef> {
+        futures::executor::block_on(self.inner.stm(n))
+    }
+
+    fn get_token(&self, id: &str) -> Option<SentienceToken> {
+        futures::executor::block_on(self.inner.get_token(id))
+    }
 }
 
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
+/// A synchronous passthrough: each method just calls its [`Cortex`] counterpart and wraps the
+/// result in an already-resolved future, so `InMemoryCortex` can satisfy [`AsyncCortex`] wherever
+/// one is required even though it has no real I/O latency to make asynchronous. No pipelining
+/// happens here; [`BlockingCortex`] driving this via `block_on` sees the same synchronous
+/// behavior as calling [`Cortex`] directly.
+#[async_trait::async_trait]
+impl AsyncCortex for InMemoryCortex {
+    async fn commit(&mut self, token: &SentienceToken, edges: &[Edge]) -> Result<String, String> {
+        Cortex::commit(self, token, edges)
     }
 
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    async fn recall_similar(&self, vec: &[f32], k: usize) -> Vec<TokenRef> {
+        Cortex::recall_similar(self, vec, k)
+    }
 
-    if norm_a == 0.0 || norm_b == 0.0 {
-        0.0
-    } else {
-        dot_product / (norm_a * norm_b)
+    async fn stm(&self, n: usize) -> Vec<TokenRef> {
+        Cortex::stm(self, n)
+    }
+
+    async fn get_token(&self, id: &str) -> Option<SentienceToken> {
+        Cortex::get_token(self, id)
     }
 }
+
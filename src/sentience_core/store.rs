@@ -0,0 +1,292 @@
+//! Content-addressed snapshot/restore for the Cortex.
+//!
+//! Tokens and edges are stored keyed by their own `token_hash`/`edge_hash`, so two identical
+//! derivations dedup to a single blob. Edges form a Merkle DAG: each stored edge carries the
+//! content hashes of its endpoints, and `load` re-hashes every token and edge, rejecting the
+//! snapshot if any recomputed hash doesn't match its stored key.
+
+use crate::sentience_core::ast::{Edge, SentienceToken};
+use crate::sentience_core::hasher::{edge_hash, token_hash, SCHEMA_VERSION};
+use crate::sentience_core::runtime::InMemoryCortex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    schema: String,
+    tokens: HashMap<String, SentienceToken>,
+    edges: HashMap<String, EdgeRecord>,
+    stm_window: Vec<String>,
+    max_stm_size: usize,
+}
+
+/// An edge plus the content hashes of the tokens it connects, making the edge set a Merkle DAG
+/// over the token store rather than a flat list of references.
+#[derive(Serialize, Deserialize)]
+struct EdgeRecord {
+    edge: Edge,
+    source_hash: String,
+    target_hash: String,
+}
+
+fn corrupt(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Serialize `cortex` to `path` as a content-addressed snapshot.
+pub fn save(cortex: &InMemoryCortex, path: &str) -> io::Result<()> {
+    let mut tokens = HashMap::new();
+    for token in cortex.all_tokens().values() {
+        tokens.insert(token_hash(&token.ast), token.clone());
+    }
+
+    let mut edges = HashMap::new();
+    for edge in cortex.all_edges().values() {
+        let key = edge_hash(&edge.source_id, &edge.edge_type.to_string(), &edge.target_id);
+        edges.insert(
+            key,
+            EdgeRecord {
+                edge: edge.clone(),
+                source_hash: edge.source_id.clone(),
+                target_hash: edge.target_id.clone(),
+            },
+        );
+    }
+
+    let snapshot = Snapshot {
+        schema: SCHEMA_VERSION.to_string(),
+        tokens,
+        edges,
+        stm_window: cortex.stm_window().to_vec(),
+        max_stm_size: cortex.max_stm_size(),
+    };
+
+    let serialized = serde_json::to_string_pretty(&snapshot)?;
+    fs::write(path, serialized)
+}
+
+/// Load a snapshot from `path`, verifying every token and edge against its own content hash and
+/// rejecting the snapshot on the first mismatch (a flipped bit, a hand-edited file, ...).
+pub fn load(path: &str) -> io::Result<InMemoryCortex> {
+    let content = fs::read_to_string(path)?;
+    let mut snapshot: Snapshot = serde_json::from_str(&content)?;
+
+    if snapshot.schema != SCHEMA_VERSION {
+        snapshot = migrate(snapshot)?;
+    }
+
+    let mut tokens = HashMap::with_capacity(snapshot.tokens.len());
+    for (key, token) in snapshot.tokens {
+        let recomputed = token_hash(&token.ast);
+        if recomputed != key {
+            return Err(corrupt(format!(
+                "token hash mismatch: stored as {} but recomputes to {}",
+                key, recomputed
+            )));
+        }
+        tokens.insert(recomputed, token);
+    }
+
+    let mut edges = HashMap::with_capacity(snapshot.edges.len());
+    for (key, record) in snapshot.edges {
+        let recomputed = edge_hash(
+            &record.edge.source_id,
+            &record.edge.edge_type.to_string(),
+            &record.edge.target_id,
+        );
+        if recomputed != key {
+            return Err(corrupt(format!(
+                "edge hash mismatch: stored as {} but recomputes to {}",
+                key, recomputed
+            )));
+        }
+        if record.source_hash != record.edge.source_id || record.target_hash != record.edge.target_id {
+            return Err(corrupt(format!(
+                "edge {} references endpoints that don't match its stored hashes",
+                key
+            )));
+        }
+        if !tokens.contains_key(&record.source_hash) || !tokens.contains_key(&record.target_hash) {
+            return Err(corrupt(format!(
+                "edge {} references a token that isn't in this snapshot",
+                key
+            )));
+        }
+        edges.insert(recomputed, record.edge);
+    }
+
+    Ok(InMemoryCortex::restore(
+        snapshot.max_stm_size,
+        tokens,
+        edges,
+        snapshot.stm_window,
+    ))
+}
+
+/// Schema tag for snapshots written before `ids.rs` added checksummed base58 token/edge ids
+/// (`chunk0-5`): every id was a plain `<prefix>_<16 hex chars>` digest with no checksum.
+const LEGACY_SCHEMA_HEX_IDS: &str = "sentience/0.1";
+
+/// Upgrade an older snapshot to [`SCHEMA_VERSION`]. Future schema bumps add another arm here
+/// instead of breaking snapshots written by an older binary.
+fn migrate(snapshot: Snapshot) -> io::Result<Snapshot> {
+    match snapshot.schema.as_str() {
+        LEGACY_SCHEMA_HEX_IDS => Ok(upgrade_hex_ids(snapshot)),
+        other => Err(corrupt(format!(
+            "unsupported snapshot schema {:?}, expected {} (or {})",
+            other, SCHEMA_VERSION, LEGACY_SCHEMA_HEX_IDS
+        ))),
+    }
+}
+
+/// Re-key every token and edge from its [`LEGACY_SCHEMA_HEX_IDS`] id to the id [`token_hash`]/
+/// [`edge_hash`] compute today, and rewrite every edge's endpoint references (and
+/// `source_hash`/`target_hash`) to follow, and bump each token's `meta.version` to
+/// [`SCHEMA_VERSION`]. Token ASTs and edge types are untouched; `load`'s hash-verification loop
+/// re-derives and checks every new id right after this returns.
+fn upgrade_hex_ids(snapshot: Snapshot) -> Snapshot {
+    let mut id_map = HashMap::with_capacity(snapshot.tokens.len());
+    let mut tokens = HashMap::with_capacity(snapshot.tokens.len());
+    for mut token in snapshot.tokens.into_values() {
+        let new_id = token_hash(&token.ast);
+        id_map.insert(token.id.clone(), new_id.clone());
+        token.id = new_id.clone();
+        token.meta.version = SCHEMA_VERSION.to_string();
+        tokens.insert(new_id, token);
+    }
+
+    let mut edges = HashMap::with_capacity(snapshot.edges.len());
+    for mut record in snapshot.edges.into_values() {
+        let source_id = id_map
+            .get(&record.source_hash)
+            .cloned()
+            .unwrap_or(record.source_hash);
+        let target_id = id_map
+            .get(&record.target_hash)
+            .cloned()
+            .unwrap_or(record.target_hash);
+        let new_id = edge_hash(&source_id, &record.edge.edge_type.to_string(), &target_id);
+
+        record.edge.id = new_id.clone();
+        record.edge.source_id = source_id.clone();
+        record.edge.target_id = target_id.clone();
+        record.source_hash = source_id;
+        record.target_hash = target_id;
+        edges.insert(new_id, record);
+    }
+
+    let stm_window = snapshot
+        .stm_window
+        .into_iter()
+        .map(|id| id_map.get(&id).cloned().unwrap_or(id))
+        .collect();
+
+    Snapshot {
+        schema: SCHEMA_VERSION.to_string(),
+        tokens,
+        edges,
+        stm_window,
+        max_stm_size: snapshot.max_stm_size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentience_core::ast::{Provenance, Span, ThoughtType, TokenMeta};
+    use crate::sentience_core::runtime::Cortex;
+
+    fn sample_token(id: &str) -> SentienceToken {
+        let ast = crate::sentience_core::ast::SentienceTokenAst::new(
+            ThoughtType::Percept,
+            Span::new(1, 1, 1, 1),
+        );
+        SentienceToken::new(
+            id.to_string(),
+            ast,
+            vec![0.1, 0.2],
+            Provenance {
+                stm_ids: Vec::new(),
+                refnet_id: "test".to_string(),
+                rules_applied: Vec::new(),
+                agent_id: "test".to_string(),
+                step_id: 0,
+                timestamp: 0,
+            },
+            TokenMeta {
+                version: SCHEMA_VERSION.to_string(),
+                strength: 1.0,
+                belief: 1.0,
+                tags: Vec::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn round_trips_through_a_file() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("sentience_store_roundtrip.json");
+        let path = path.to_str().unwrap();
+
+        let mut cortex = InMemoryCortex::new(8);
+        let token = sample_token("temp_id");
+        Cortex::commit(&mut cortex, &token, &[]).unwrap();
+
+        save(&cortex, path).unwrap();
+        let restored = load(path).unwrap();
+
+        assert_eq!(restored.all_tokens().len(), cortex.all_tokens().len());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn rejects_tampered_token() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("sentience_store_tampered.json");
+        let path = path.to_str().unwrap();
+
+        let mut cortex = InMemoryCortex::new(8);
+        let token = sample_token("temp_id");
+        Cortex::commit(&mut cortex, &token, &[]).unwrap();
+        save(&cortex, path).unwrap();
+
+        let raw = fs::read_to_string(path).unwrap();
+        let tampered = raw.replace("\"Percept\"", "\"Action\"");
+        fs::write(path, tampered).unwrap();
+
+        assert!(load(path).is_err());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn migrates_legacy_hex_id_snapshot() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("sentience_store_legacy.json");
+        let path = path.to_str().unwrap();
+
+        let legacy_id = "mem_deadbeefcafef00d";
+        let token = sample_token(legacy_id);
+
+        let mut tokens = HashMap::new();
+        tokens.insert(legacy_id.to_string(), token);
+
+        let legacy = Snapshot {
+            schema: LEGACY_SCHEMA_HEX_IDS.to_string(),
+            tokens,
+            edges: HashMap::new(),
+            stm_window: vec![legacy_id.to_string()],
+            max_stm_size: 8,
+        };
+        fs::write(path, serde_json::to_string_pretty(&legacy).unwrap()).unwrap();
+
+        let restored = load(path).unwrap();
+
+        assert_eq!(restored.all_tokens().len(), 1);
+        let new_id = restored.all_tokens().keys().next().unwrap();
+        assert_ne!(new_id, legacy_id);
+        assert_eq!(restored.stm_window(), &[new_id.clone()]);
+        let _ = fs::remove_file(path);
+    }
+}
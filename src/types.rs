@@ -1,44 +1,195 @@
+use crate::diagnostics::Span;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Program {
-    pub statements: Vec<Statement>,
+    pub statements: Vec<Spanned<Statement>>,
+}
+
+/// A parsed node paired with the source span it was parsed from, so later passes (diagnostics,
+/// REPL error reporting) can point back at the original text without re-deriving it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Statement {
     AgentDeclaration {
         name: String,
-        body: Vec<Statement>,
+        body: Vec<Spanned<Statement>>,
     },
     MemDeclaration {
         target: String,
     },
     OnInput {
         param: String,
-        body: Vec<Statement>,
+        body: Vec<Spanned<Statement>>,
     },
     Reflect {
-        body: Vec<Statement>,
+        body: Vec<Spanned<Statement>>,
     },
     ReflectAccess {
         mem_target: String,
         key: String,
     },
     Train {
-        body: Vec<Statement>,
+        body: Vec<Spanned<Statement>>,
+    },
+    /// Single-line `train <expr> -> "label"`: embeds `input` and associates it with `label` so
+    /// later `recall`s can retrieve it by nearest-neighbor similarity.
+    TrainAssociation {
+        input: Expr,
+        label: String,
     },
     Evolve {
-        body: Vec<Statement>,
+        body: Vec<Spanned<Statement>>,
     },
-    Goal(String),
+    Goal(Expr),
     Embed {
         source: String,
         target: String,
     },
+    /// `recall <ident> -> <dotted.path>`: embeds the identifier's current value and writes the
+    /// nearest-neighbor label found for it to short-term memory under `target`.
+    Recall {
+        source: String,
+        target: String,
+    },
+    /// `import <name>`: loads and registers the agent(s) declared in `<name>.sent`, resolved via
+    /// [`crate::modules::ModuleLoader`].
+    Import {
+        name: String,
+    },
+    /// `enter <AgentName>`: delegates to the named agent's goals, looked up in `ctx.agents`, which
+    /// holds only agents pulled in by `import` (a local `AgentDeclaration` is not added to it).
+    Enter {
+        name: String,
+    },
     IfContextIncludes {
-        values: Vec<String>,
-        body: Vec<Statement>,
+        values: Vec<Expr>,
+        /// Trailing `and <expr>` clause, e.g. `if context includes [...] and score >= threshold`.
+        condition: Option<Expr>,
+        body: Vec<Spanned<Statement>>,
+    },
+    Match {
+        target: Expr,
+        arms: Vec<MatchArm>,
     },
     Print(String),
-    Assignment(String, String),
+    Assignment(String, Expr),
     Unknown(String),
 }
+
+/// One `when <pattern> { ... }` arm of a `match` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Vec<Spanned<Statement>>,
+}
+
+/// A word sequence matched against the evaluator's target expression, tokenized from a `when`
+/// arm's pattern string at parse time. Literal words must match the corresponding input word
+/// exactly; `$name` metavariables bind a run of input words to `name` in short-term memory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    pub segments: Vec<PatternSegment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatternSegment {
+    Literal(String),
+    Var(String),
+}
+
+impl Pattern {
+    /// Tokenize a pattern string into literal words and `$name` metavariables, splitting on
+    /// whitespace the same way the matcher later splits the input it's tested against.
+    pub fn parse(s: &str) -> Self {
+        let segments = s
+            .split_whitespace()
+            .map(|word| match word.strip_prefix('$') {
+                Some(name) => PatternSegment::Var(name.to_string()),
+                None => PatternSegment::Literal(word.to_string()),
+            })
+            .collect();
+        Pattern { segments }
+    }
+
+    /// Test `input` against this pattern by splitting it into words and walking both sequences
+    /// left to right: a literal segment must match the word at the current position exactly; a
+    /// metavariable captures words non-greedily, up to (but not including) the next literal
+    /// anchor in the pattern, or to the end of input if it's trailing. Returns the captured
+    /// `(name, value)` bindings in order, or `None` if the shape doesn't match.
+    pub fn match_input(&self, input: &str) -> Option<Vec<(String, String)>> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let mut bindings = Vec::new();
+        let mut pos = 0;
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PatternSegment::Literal(lit) => {
+                    if words.get(pos) != Some(&lit.as_str()) {
+                        return None;
+                    }
+                    pos += 1;
+                }
+                PatternSegment::Var(name) => {
+                    let next_literal = self.segments[i + 1..].iter().find_map(|seg| match seg {
+                        PatternSegment::Literal(lit) => Some(lit.as_str()),
+                        PatternSegment::Var(_) => None,
+                    });
+                    let end = match next_literal {
+                        Some(lit) => pos + words[pos..].iter().position(|w| *w == lit)?,
+                        None => words.len(),
+                    };
+                    bindings.push((name.clone(), words[pos..end].join(" ")));
+                    pos = end;
+                }
+            }
+        }
+
+        if pos == words.len() {
+            Some(bindings)
+        } else {
+            None
+        }
+    }
+}
+
+/// An expression in the Sentience DSL, produced by the parser's precedence-climbing (Pratt)
+/// parser. Used anywhere the grammar used to accept only a string or identifier literal: goal
+/// values, assignment right-hand sides, and `if context includes [...]` conditions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    Str(String),
+    Bool(bool),
+    Ident(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    Member(Box<Expr>, String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+    NotEq,
+    And,
+    Or,
+}